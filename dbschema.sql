@@ -11,9 +11,30 @@ CREATE TABLE iam.user (
   password_hash TEXT NOT NULL,
   bio TEXT,
   image_url TEXT,
+  deactivated BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE UNIQUE INDEX user_unique_email ON iam.user (lower(email));
+CREATE UNIQUE INDEX user_unique_username ON iam.user (lower(username));
+
+CREATE TABLE iam.audit_log (
+  id UUID CONSTRAINT audit_log_pk PRIMARY KEY,
+  time TIMESTAMP WITH TIME ZONE NOT NULL,
+  action TEXT NOT NULL,
+  actor_id UUID,
+  target TEXT,
+  ip TEXT
+);
+
+CREATE TABLE iam.follows (
+  id UUID CONSTRAINT follows_pk PRIMARY KEY,
+  created TIMESTAMP WITH TIME ZONE NOT NULL,
+  updated TIMESTAMP WITH TIME ZONE,
+  version BIGINT NOT NULL,
+  follower_id UUID NOT NULL REFERENCES iam.user (id) ON DELETE CASCADE,
+  following_id UUID NOT NULL REFERENCES iam.user (id) ON DELETE CASCADE,
 
-  CONSTRAINT user_unique_username UNIQUE (username),
-  CONSTRAINT user_unique_email UNIQUE (email)
+  CONSTRAINT follows_unique_pair UNIQUE (follower_id, following_id)
 );
 
 CREATE SCHEMA blog;
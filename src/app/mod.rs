@@ -1,4 +1,3 @@
+pub mod query;
 pub mod resource;
 pub mod use_case;
-
-pub mod query {}
@@ -0,0 +1,371 @@
+use crate::domain::datatype::JsonPointer;
+use crate::error::resource::{FieldExtension, ValidationErrorKind, ValidationFieldError};
+
+/// Page of a list query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// Maximum number of items in the page.
+    pub limit: u32,
+    /// Number of items skipped before the page.
+    pub offset: u32,
+}
+
+impl Pagination {
+    pub const DEFAULT_LIMIT: u32 = 20;
+    pub const MAX_LIMIT: u32 = 100;
+    /// Default of the maximum offset.
+    ///
+    /// The database scans and discards every skipped row, so large offsets are rejected.
+    pub const DEFAULT_MAX_OFFSET: u32 = 10_000;
+
+    /// Parse the pagination from the raw `limit` and `offset` query params.
+    pub fn from_query(
+        limit: Option<&str>,
+        offset: Option<&str>,
+        max_offset: u32,
+    ) -> Result<Self, Vec<ValidationFieldError>> {
+        let mut errors = Vec::new();
+        let mut pagination = Self::default();
+
+        if let Some(limit) = limit {
+            match parse_u32(limit, "limit") {
+                Ok(0) => errors.push(ValidationFieldError::from_resource::<u32>(
+                    limit.into(),
+                    JsonPointer::new(["limit"]),
+                    vec![ValidationErrorKind::Minimum(1)],
+                )),
+                Ok(value) if value > Self::MAX_LIMIT => {
+                    errors.push(ValidationFieldError::from_resource::<u32>(
+                        limit.into(),
+                        JsonPointer::new(["limit"]),
+                        vec![ValidationErrorKind::Maximum(Self::MAX_LIMIT.into())],
+                    ))
+                }
+                Ok(value) => pagination.limit = value,
+                Err(err) => errors.push(err),
+            }
+        }
+
+        if let Some(offset) = offset {
+            match parse_u32(offset, "offset") {
+                Ok(value) if value > max_offset => errors.push(
+                    ValidationFieldError::from_resource::<u32>(
+                        offset.into(),
+                        JsonPointer::new(["offset"]),
+                        vec![ValidationErrorKind::Maximum(max_offset.into())],
+                    )
+                    .with_extension(FieldExtension::CursorPagination { max_offset }),
+                ),
+                Ok(value) => pagination.offset = value,
+                Err(err) => errors.push(err),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(pagination)
+    }
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            limit: Self::DEFAULT_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+fn parse_u32(value: &str, field: &str) -> Result<u32, ValidationFieldError> {
+    value.parse().map_err(|_| {
+        ValidationFieldError::from_resource::<u32>(
+            value.into(),
+            JsonPointer::new([field]),
+            vec![ValidationErrorKind::InvalidType],
+        )
+    })
+}
+
+pub mod article {
+    use std::str::FromStr;
+
+    use derive_more::Display;
+    use serde::{Deserialize, Serialize};
+
+    use super::Pagination;
+    use crate::{
+        base::{resource_id, ResourceID},
+        domain::datatype::JsonPointer,
+        error::resource::{ValidationError, ValidationErrorKind, ValidationFieldError},
+    };
+
+    /// Raw query params of the article list.
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    pub struct ArticleListParams {
+        pub tag: Option<String>,
+        pub author: Option<String>,
+        pub favorited: Option<String>,
+        pub limit: Option<String>,
+        pub offset: Option<String>,
+        pub sort: Option<String>,
+        pub q: Option<String>,
+    }
+
+    resource_id!(ArticleListParams, "blog::ArticleListParams");
+
+    /// Raw query params of the slug preview.
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    pub struct SlugifyParams {
+        pub title: Option<String>,
+    }
+
+    resource_id!(SlugifyParams, "blog::SlugifyParams");
+
+    /// Article list ordering.
+    #[derive(Debug, Display, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum ArticleSort {
+        /// Most recent articles first.
+        #[default]
+        #[display(fmt = "recent")]
+        Recent,
+        /// Oldest articles first.
+        #[display(fmt = "oldest")]
+        Oldest,
+        /// Most favorited articles first.
+        #[display(fmt = "popular")]
+        Popular,
+    }
+
+    impl ResourceID for ArticleSort {
+        fn resource_id() -> &'static str {
+            "blog::article_sort"
+        }
+    }
+
+    impl FromStr for ArticleSort {
+        type Err = ValidationFieldError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "recent" => Ok(Self::Recent),
+                "oldest" => Ok(Self::Oldest),
+                "popular" => Ok(Self::Popular),
+                _ => Err(ValidationFieldError::from_resource::<Self>(
+                    s.into(),
+                    JsonPointer::root(),
+                    vec![ValidationErrorKind::UnknownVariant],
+                )),
+            }
+        }
+    }
+
+    /// Validated article list query.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ArticleListQuery {
+        /// Articles tagged with.
+        pub tag: Option<String>,
+        /// Articles written by the author username.
+        pub author: Option<String>,
+        /// Articles favorited by the username.
+        pub favorited: Option<String>,
+        /// Full text search.
+        ///
+        /// Search results are ordered by relevance, so it can not be combined with `sort`.
+        pub q: Option<String>,
+        pub sort: ArticleSort,
+        pub pagination: Pagination,
+    }
+
+    impl ArticleListQuery {
+        pub fn from_params(
+            params: ArticleListParams,
+            max_offset: u32,
+        ) -> Result<Self, ValidationError<ArticleListParams>> {
+            let mut errors = Vec::new();
+
+            let pagination = match Pagination::from_query(
+                params.limit.as_deref(),
+                params.offset.as_deref(),
+                max_offset,
+            ) {
+                Ok(pagination) => pagination,
+                Err(mut errs) => {
+                    errors.append(&mut errs);
+                    Pagination::default()
+                }
+            };
+
+            let mut sort = ArticleSort::default();
+            if let Some(value) = params.sort.as_deref() {
+                match ArticleSort::from_str(value) {
+                    Ok(value) => sort = value,
+                    Err(mut err) => {
+                        err.path = JsonPointer::new(["sort"]);
+                        errors.push(err);
+                    }
+                }
+
+                if params.q.is_some() {
+                    errors.push(ValidationFieldError::from_resource::<ArticleSort>(
+                        value.into(),
+                        JsonPointer::new(["sort"]),
+                        vec![ValidationErrorKind::Invalid],
+                    ));
+                }
+            }
+
+            if !errors.is_empty() {
+                return Err(ValidationError::from_resource(params, errors));
+            }
+
+            Ok(Self {
+                tag: params.tag,
+                author: params.author,
+                favorited: params.favorited,
+                q: params.q,
+                sort,
+                pagination,
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod article_list_query_test {
+        use pretty_assertions::assert_eq;
+
+        use super::{ArticleListParams, ArticleListQuery, ArticleSort, Pagination};
+        use crate::error::resource::{FieldExtension, ValidationErrorKind};
+
+        #[test]
+        fn parse_default_query() {
+            let query = ArticleListQuery::from_params(
+                ArticleListParams::default(),
+                Pagination::DEFAULT_MAX_OFFSET,
+            )
+            .expect("Expect to parse a empty query");
+
+            assert_eq!(query.sort, ArticleSort::Recent);
+            assert_eq!(query.pagination, Pagination::default());
+        }
+
+        #[test]
+        fn parse_combined_query() {
+            let params = ArticleListParams {
+                tag: Some("rust".into()),
+                author: Some("jake".into()),
+                favorited: Some("jane".into()),
+                limit: Some("50".into()),
+                offset: Some("100".into()),
+                sort: Some("popular".into()),
+                q: None,
+            };
+
+            let query = ArticleListQuery::from_params(params, Pagination::DEFAULT_MAX_OFFSET)
+                .expect("Expect a valid query");
+
+            assert_eq!(
+                query,
+                ArticleListQuery {
+                    tag: Some("rust".into()),
+                    author: Some("jake".into()),
+                    favorited: Some("jane".into()),
+                    q: None,
+                    sort: ArticleSort::Popular,
+                    pagination: Pagination {
+                        limit: 50,
+                        offset: 100
+                    },
+                }
+            );
+        }
+
+        #[test]
+        fn validate_invalid_query() {
+            let params = ArticleListParams {
+                limit: Some("1000".into()),
+                offset: Some("-1".into()),
+                sort: Some("random".into()),
+                ..Default::default()
+            };
+
+            let err = ArticleListQuery::from_params(params, Pagination::DEFAULT_MAX_OFFSET)
+                .expect_err("Expect a invalid query");
+            let fields: Vec<_> = err
+                .fields
+                .iter()
+                .map(|field| (field.path.to_string(), field.kinds.clone()))
+                .collect();
+
+            assert_eq!(
+                fields,
+                vec![
+                    (
+                        "/limit".into(),
+                        vec![ValidationErrorKind::Maximum(Pagination::MAX_LIMIT.into())]
+                    ),
+                    ("/offset".into(), vec![ValidationErrorKind::InvalidType]),
+                    ("/sort".into(), vec![ValidationErrorKind::UnknownVariant]),
+                ]
+            );
+        }
+
+        #[test]
+        fn validate_offset_above_maximum() {
+            let params = ArticleListParams {
+                offset: Some("10001".into()),
+                ..Default::default()
+            };
+
+            let err = ArticleListQuery::from_params(params, Pagination::DEFAULT_MAX_OFFSET)
+                .expect_err("Expect a offset above the maximum");
+
+            assert_eq!(err.fields.len(), 1);
+            assert_eq!(err.fields[0].path, "/offset");
+            assert_eq!(
+                err.fields[0].kinds,
+                vec![ValidationErrorKind::Maximum(
+                    Pagination::DEFAULT_MAX_OFFSET.into()
+                )]
+            );
+            assert_eq!(
+                err.fields[0].extension,
+                Some(FieldExtension::CursorPagination {
+                    max_offset: Pagination::DEFAULT_MAX_OFFSET
+                })
+            );
+
+            let params = ArticleListParams {
+                offset: Some("500".into()),
+                ..Default::default()
+            };
+            let query = ArticleListQuery::from_params(params, 1000)
+                .expect("Expect a offset within a configured maximum");
+            assert_eq!(query.pagination.offset, 500);
+
+            let params = ArticleListParams {
+                offset: Some("500".into()),
+                ..Default::default()
+            };
+            ArticleListQuery::from_params(params, 100)
+                .expect_err("Expect a offset above a configured maximum");
+        }
+
+        #[test]
+        fn validate_search_with_sort() {
+            let params = ArticleListParams {
+                q: Some("async rust".into()),
+                sort: Some("oldest".into()),
+                ..Default::default()
+            };
+
+            let err = ArticleListQuery::from_params(params, Pagination::DEFAULT_MAX_OFFSET)
+                .expect_err("Expect search to not combine with sort");
+
+            assert_eq!(err.fields.len(), 1);
+            assert_eq!(err.fields[0].path, "/sort");
+            assert_eq!(err.fields[0].kinds, vec![ValidationErrorKind::Invalid]);
+        }
+    }
+}
@@ -8,6 +8,13 @@ macro_rules! resource_response {
             pub version: u32,
             $(pub $field: $field_ty),+
         }
+
+        impl $name {
+            /// Opaque tag of the resource version.
+            pub fn version_tag(&self) -> String {
+                crate::domain::entity::version_tag(self.version)
+            }
+        }
     };
 }
 
@@ -18,6 +25,22 @@ pub mod iam {
     use uuid::Uuid;
 
     use crate::base::resource_id;
+    use crate::domain::datatype::{
+        security::{TokenIssuer, TokenSubject},
+        JsonPointer,
+    };
+    use crate::error::resource::{ValidationError, ValidationErrorKind, ValidationFieldError};
+
+    /// Body envelope nesting the user under the `user` key, as the RealWorld spec.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct UserResource<T> {
+        pub user: T,
+    }
+
+    impl<T> UserResource<T> {
+        /// Path of the enveloped user.
+        pub const PATH: &'static str = "/user";
+    }
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct CreateUser<'a> {
@@ -44,6 +67,57 @@ pub mod iam {
 
     resource_id!(UserCredential<'_>, "iam::UserCredential");
 
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RefreshTokenBody<'a> {
+        pub refresh_token: &'a str,
+    }
+
+    /// Credential envelope with every field optional, to report the missing ones.
+    #[derive(Debug, Clone, Default, Deserialize)]
+    pub struct CredentialBody<'a> {
+        #[serde(default, borrow)]
+        pub user: PartialCredential<'a>,
+    }
+
+    #[derive(Debug, Clone, Default, Deserialize)]
+    pub struct PartialCredential<'a> {
+        pub email: Option<&'a str>,
+        pub password: Option<&'a str>,
+    }
+
+    impl<'a> PartialCredential<'a> {
+        /// Require the email and password, reporting both when missing.
+        pub fn require(self) -> Result<UserCredential<'a>, ValidationError<UserCredential<'a>>> {
+            match (self.email, self.password) {
+                (Some(email), Some(password)) => Ok(UserCredential { email, password }),
+                (email, password) => {
+                    let mut fields = Vec::new();
+                    if email.is_none() {
+                        fields.push(ValidationFieldError::new(
+                            "base::email",
+                            String::new(),
+                            JsonPointer::new(["email"]),
+                            vec![ValidationErrorKind::Required],
+                        ));
+                    }
+                    if password.is_none() {
+                        fields.push(ValidationFieldError::new(
+                            "base::password",
+                            String::new(),
+                            JsonPointer::new(["password"]),
+                            vec![ValidationErrorKind::Required],
+                        ));
+                    }
+                    let credential = UserCredential {
+                        email: email.unwrap_or_default(),
+                        password: "",
+                    };
+                    Err(ValidationError::from_resource(credential, fields))
+                }
+            }
+        }
+    }
+
     resource_response! {
         struct UserResponse;
         username: String,
@@ -54,30 +128,116 @@ pub mod iam {
 
     resource_id!(UserResponse, "iam::User");
 
+    /// Outcome of a user registration.
+    #[derive(Debug, Clone)]
+    pub enum UserRegistration {
+        Created(UserResponse),
+        /// Email already registered, concealed from the client.
+        Concealed,
+    }
+
+    /// Generic registration response, the same whether the email was registered or not.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct RegistrationPendingResponse {
+        pub message: &'static str,
+    }
+
+    impl RegistrationPendingResponse {
+        pub const MESSAGE: &'static str = "Check your email to continue the registration";
+    }
+
     #[derive(Debug, Clone, Serialize)]
     pub struct AuthenticateUserResponse {
+        #[serde(flatten)]
         pub user: UserResponse,
         pub token: String,
+        /// Token to renew the access token, without the credential.
+        pub refresh_token: String,
     }
 
     resource_id!(AuthenticateUserResponse, "iam::AuthenticateUser");
+
+    /// Every data held about a user, for data portability requests.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct UserExportResponse {
+        pub user: UserResponse,
+        /// Usernames the user follows.
+        pub following: Vec<String>,
+        /// Usernames following the user.
+        pub followers: Vec<String>,
+    }
+
+    resource_id!(UserExportResponse, "iam::UserExport");
+
+    /// Decoded claims of a authentication token, without the signature.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct TokenIntrospectionResponse {
+        pub sub: TokenSubject,
+        pub iss: TokenIssuer,
+        /// Audience, not issued by the service.
+        pub aud: Option<String>,
+        pub iat: u64,
+        pub exp: u64,
+        /// Token id, not issued by the service.
+        pub jti: Option<String>,
+        pub expired: bool,
+        /// Always false, the issued tokens are not revoked before the expiration.
+        pub revoked: bool,
+    }
+
+    resource_id!(TokenIntrospectionResponse, "iam::TokenIntrospection");
 }
 
 pub mod profile {
+    use std::collections::HashMap;
+
     use chrono::{DateTime, Utc};
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
+    use url::Url;
     use uuid::Uuid;
 
+    use crate::base::resource_id;
+
     #[derive(Debug, Clone, Deserialize)]
     pub struct PutFollowDto<'a> {
         pub following_id: &'a str,
     }
 
+    /// Profile to follow or unfollow, addressed by the username in the path.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct FollowProfile<'a> {
+        pub username: &'a str,
+    }
+
+    resource_id!(FollowProfile<'_>, "profile::Follow");
+
     resource_response! {
         struct ProfileResponse;
         username: String,
-        bio: String,
-        image_url: String,
+        bio: Option<String>,
+        image_url: Option<Url>,
+        following: bool,
+    }
+
+    resource_id!(ProfileResponse, "profile::Profile");
+
+    /// Profiles to check whether the authenticated user follows, by user id.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct FollowingStatusQuery {
+        pub ids: Vec<Uuid>,
+    }
+
+    impl FollowingStatusQuery {
+        /// Maximum ids checked at once.
+        pub const MAX_IDS: usize = 100;
+    }
+
+    resource_id!(FollowingStatusQuery, "profile::FollowingStatus");
+
+    /// Whether the authenticated user follows each of the checked profiles.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct FollowingStatusResponse {
+        pub following: HashMap<Uuid, bool>,
     }
 
     resource_response! {
@@ -87,9 +247,21 @@ pub mod profile {
     }
 }
 
+pub mod health {
+    use serde::Serialize;
+
+    /// Readiness of the service to handle requests.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct ReadinessResponse {
+        pub ready: bool,
+        /// Required database tables not found.
+        pub missing_tables: Vec<&'static str>,
+    }
+}
+
 pub mod article {
     use chrono::{DateTime, Utc};
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
     #[derive(Debug, Clone, Deserialize)]
@@ -110,6 +282,12 @@ pub mod article {
         version_id: Uuid,
     }
 
+    /// Slug a article title would have.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct SlugPreviewResponse {
+        pub slug: String,
+    }
+
     #[derive(Debug, Clone, Deserialize)]
     pub struct PutArticleFavorite<'a> {
         pub article_id: &'a str,
@@ -1,164 +1,950 @@
 pub mod iam {
-    use std::time::Duration;
+    use std::{net::IpAddr, time::Duration};
 
     use sqlx::PgPool;
     use uuid::Uuid;
 
     use crate::{
         app::resource::iam::{
-            AuthenticateUserResponse, CreateUser, UpdateUser, UserCredential, UserResponse,
+            AuthenticateUserResponse, CreateUser, RefreshTokenBody, TokenIntrospectionResponse,
+            UpdateUser, UserCredential, UserExportResponse, UserRegistration, UserResponse,
         },
         domain::{
-            datatype::security::{Token, TokenPayload, TokenSubject},
+            datatype::{
+                audit::{AuditAction, AuditEvent},
+                security::{
+                    PasswordHashError, PasswordPolicy, Token, TokenKind, TokenPayload, TokenSubject,
+                },
+                Bio, Email, JsonPointer, Username,
+            },
             entity::{iam::User, Entity},
-            service::{PasswordHashService, TokenEncryptionService},
+            service::{AuditSink, PasswordHashService, TokenEncryptionService},
         },
         error::{
             app::ApplicationError,
-            resource::{NotFoundError, ValidationError, ValidationErrorKind, ValidationFieldError},
-            security::AuthenticationError,
+            operation::OperationError,
+            persistence::MutationError,
+            resource::{
+                FieldExtension, NotFoundError, ValidationError, ValidationErrorKind,
+                ValidationFieldError,
+            },
+            security::{AuthenticationError, ForbiddenError},
+        },
+        infra::database::{
+            constraint,
+            repository::{self, ConflictingIdentifiers},
         },
-        infra::database::repository,
     };
 
+    /// Authorize a token subject to act on the user `id`.
+    ///
+    /// Public tokens are valid, but don't grant access to any user.
+    fn authorize_user<R>(subject: &TokenSubject, id: Uuid) -> Result<(), ApplicationError<R>> {
+        match subject {
+            TokenSubject::User(subject) if *subject == id => Ok(()),
+            TokenSubject::User(_) => Err(AuthenticationError::InvalidToken.into()),
+            TokenSubject::Public => Err(ForbiddenError::AccessDenied.into()),
+        }
+    }
+
+    /// Find the user acting with a token subject.
+    ///
+    /// A token issued before the deactivation is still valid, but no longer acts on the account.
+    async fn find_active_user<R>(pool: &PgPool, id: Uuid) -> Result<User, ApplicationError<R>> {
+        let user = repository::find_user_by_id(pool, id)
+            .await?
+            .ok_or_else(|| NotFoundError::from_resource::<UserResponse>(id))?;
+
+        if *user.deactivated() {
+            return Err(OperationError::AccountDeactivated.into());
+        }
+
+        Ok(user)
+    }
+
     mod validation {
         use super::*;
 
+        /// Validate a registration password against the policy.
+        ///
+        /// A password equal to the username or email, ignoring the case, is the first
+        /// guess of a attacker, so it is rejected regardless of the policy.
+        fn validate_password(
+            policy: &PasswordPolicy,
+            dto: &CreateUser<'_>,
+        ) -> Vec<ValidationErrorKind> {
+            let mut kinds = policy.validate(dto.password);
+
+            let password = dto.password.to_lowercase();
+            if [dto.username, dto.email]
+                .iter()
+                .any(|identifier| identifier.to_lowercase() == password)
+            {
+                kinds.push(ValidationErrorKind::Invalid);
+            }
+
+            kinds
+        }
+
+        /// Validate a user registration.
+        ///
+        /// Returns the normalized email and the username, and whether the email is
+        /// already registered when concealed from the errors.
         pub async fn create_user<'dto>(
             pool: &PgPool,
             dto: &CreateUser<'dto>,
-        ) -> Result<(), ApplicationError<CreateUser<'dto>>> {
+            conceal_existing_email: bool,
+        ) -> Result<(Email, Username, bool), ApplicationError<CreateUser<'dto>>> {
             let mut errors = Vec::new();
 
-            let emails = repository::email_exists(pool, [&dto.email.into()]).await?;
-            if !emails.is_empty() {
-                errors.push(ValidationFieldError::new(
-                    "base::email",
-                    dto.email.into(),
-                    "/email".into(),
-                    vec![ValidationErrorKind::AlreadyExists],
-                ));
+            let (email, email_exists) = match dto.email.parse::<Email>() {
+                Ok(email) => {
+                    let emails = repository::email_exists(pool, [&email.as_str().into()]).await?;
+                    let email_exists = !emails.is_empty();
+                    if email_exists && !conceal_existing_email {
+                        errors.push(already_exists("base::email", dto.email, "email"));
+                    }
+                    (Some(email), email_exists)
+                }
+                Err(err) => {
+                    errors.push(err);
+                    (None, false)
+                }
+            };
+
+            let username = match Username::new(dto.username) {
+                Ok(username) => {
+                    let usernames =
+                        repository::username_exists(pool, [&username.as_str().into()]).await?;
+                    if !usernames.is_empty() {
+                        errors.push(already_exists("base::username", dto.username, "username"));
+                    }
+                    Some(username)
+                }
+                Err(err) => {
+                    errors.push(err);
+                    None
+                }
+            };
+
+            let policy = PasswordPolicy::default();
+            let kinds = validate_password(&policy, dto);
+            if !kinds.is_empty() {
+                errors.push(
+                    ValidationFieldError::new(
+                        "base::password",
+                        String::new(),
+                        JsonPointer::new(["password"]),
+                        kinds,
+                    )
+                    .with_extension(FieldExtension::PasswordPolicy(policy)),
+                );
             }
 
-            let usernames = repository::username_exists(pool, [&dto.username.into()]).await?;
-            if !usernames.is_empty() {
-                errors.push(ValidationFieldError::new(
-                    "base::username",
-                    dto.username.into(),
-                    "/username".into(),
-                    vec![ValidationErrorKind::AlreadyExists],
-                ));
+            match (email, username) {
+                (Some(email), Some(username)) if errors.is_empty() => {
+                    Ok((email, username, email_exists))
+                }
+                _ => Err(registration_error(dto, errors).into()),
+            }
+        }
+
+        /// Error of a password the hash service refused.
+        pub fn password_hash_error(err: PasswordHashError) -> ValidationFieldError {
+            let kind = match err {
+                PasswordHashError::PasswordTooLong(max) => {
+                    ValidationErrorKind::MaxLength(max as u64)
+                }
+                _ => ValidationErrorKind::Invalid,
+            };
+            ValidationFieldError::new(
+                "base::password",
+                String::new(),
+                JsonPointer::new(["password"]),
+                vec![kind],
+            )
+        }
+
+        fn already_exists(type_id: &'static str, value: &str, field: &str) -> ValidationFieldError {
+            ValidationFieldError::new(
+                type_id,
+                value.into(),
+                JsonPointer::new([field]),
+                vec![ValidationErrorKind::AlreadyExists],
+            )
+        }
+
+        /// Errors of the registered identifiers, without the concealed email.
+        pub fn conflicting_fields(
+            dto: &CreateUser<'_>,
+            conflicts: ConflictingIdentifiers,
+            conceal_existing_email: bool,
+        ) -> Vec<ValidationFieldError> {
+            let mut fields = Vec::new();
+            if conflicts.email && !conceal_existing_email {
+                fields.push(already_exists("base::email", dto.email, "email"));
+            }
+            if conflicts.username {
+                fields.push(already_exists("base::username", dto.username, "username"));
+            }
+            fields
+        }
+
+        pub fn registration_error<'dto>(
+            dto: &CreateUser<'dto>,
+            fields: Vec<ValidationFieldError>,
+        ) -> ValidationError<CreateUser<'dto>> {
+            // the attempted password is not responded back
+            let resource = CreateUser {
+                password: "",
+                ..dto.clone()
+            };
+            ValidationError::from_resource(resource, fields)
+        }
+
+        #[cfg(test)]
+        mod validation_test {
+            use pretty_assertions::assert_eq;
+
+            use super::validate_password;
+            use crate::{
+                app::resource::iam::CreateUser, domain::datatype::security::PasswordPolicy,
+                error::resource::ValidationErrorKind,
+            };
+
+            fn registration(password: &str) -> CreateUser<'_> {
+                CreateUser {
+                    username: "user12345",
+                    email: "user@email.com",
+                    password,
+                }
+            }
+
+            #[test]
+            fn accept_valid_password() {
+                let policy = PasswordPolicy::default();
+                assert_eq!(
+                    validate_password(&policy, &registration("secure:12345678")),
+                    vec![]
+                );
             }
 
-            if !errors.is_empty() {
-                return Err(ValidationError::from_resource(dto.clone(), errors).into());
+            #[test]
+            fn reject_too_short_password() {
+                let policy = PasswordPolicy::default();
+                assert_eq!(
+                    validate_password(&policy, &registration("no")),
+                    vec![ValidationErrorKind::MinLength(8)]
+                );
             }
 
-            Ok(())
+            #[test]
+            fn reject_password_equal_to_identifier() {
+                let policy = PasswordPolicy::default();
+                for password in ["user12345", "USER12345", "user@email.com"] {
+                    assert_eq!(
+                        validate_password(&policy, &registration(password)),
+                        vec![ValidationErrorKind::Invalid],
+                        "{password}"
+                    );
+                }
+            }
         }
     }
 
+    /// Register a user.
+    ///
+    /// With `conceal_existing_email`, a registration with a already registered email
+    /// doesn't fail, so the registration can't be used to enumerate the users. The password is
+    /// hashed in both paths, so the response time doesn't tell them apart either.
     pub async fn create_user<'dto, HS: PasswordHashService>(
         pool: &PgPool,
         hash_service: &HS,
         id: Uuid,
         dto: CreateUser<'dto>,
-    ) -> Result<UserResponse, ApplicationError<CreateUser<'dto>>> {
-        validation::create_user(pool, &dto).await?;
+        conceal_existing_email: bool,
+    ) -> Result<UserRegistration, ApplicationError<CreateUser<'dto>>> {
+        let (email, username, email_exists) =
+            validation::create_user(pool, &dto, conceal_existing_email).await?;
 
-        let password_hash = hash_service.hash_password(dto.password).map_err(|_| {
-            ValidationError::from_resource(
-                dto.clone(),
-                vec![ValidationFieldError::new(
-                    "base::password",
-                    dto.password.into(),
-                    "/password".into(),
-                    vec![ValidationErrorKind::Invalid],
-                )],
-            )
+        // hashed for a registered email as well, so the response time doesn't tell it apart
+        let password_hash = hash_service.hash_password(dto.password).map_err(|err| {
+            validation::registration_error(&dto, vec![validation::password_hash_error(err)])
         })?;
-        let user = User::new(id, dto.email.into(), dto.username.into(), password_hash);
+        if email_exists {
+            // TODO: send a "you already have an account" email
+            tracing::info!(
+                target = "use_case::iam::create_user",
+                "concealed existing email"
+            );
+            return Ok(UserRegistration::Concealed);
+        }
+
+        let user = User::new(id, email.into(), username.into(), password_hash);
 
         // TODO: validate if user id already exists
 
-        repository::insert_users(pool, [&user]).await?;
+        let conflicts = match repository::create_user(pool, &user).await {
+            Ok(conflicts) => conflicts,
+            // a concurrent registration took the email or username after the check
+            Err(MutationError::Persistence(err)) => match constraint::violated_unique_field(&err) {
+                Some(field) => {
+                    tracing::info!(
+                        target = "use_case::iam::create_user",
+                        field = field.type_id,
+                        "concurrent registration of a unique field"
+                    );
+                    ConflictingIdentifiers {
+                        email: field.path == "/email",
+                        username: field.path == "/username",
+                    }
+                }
+                None => return Err(err.into()),
+            },
+            Err(err) => return Err(err.into()),
+        };
 
-        Ok(user.into())
+        if conflicts.any() {
+            let fields = validation::conflicting_fields(&dto, conflicts, conceal_existing_email);
+            if fields.is_empty() {
+                return Ok(UserRegistration::Concealed);
+            }
+            return Err(validation::registration_error(&dto, fields).into());
+        }
+
+        Ok(UserRegistration::Created(user.into()))
+    }
+
+    /// Persist the password hashed with the current params.
+    ///
+    /// A failure is only logged, the old hash is still valid to authenticate.
+    async fn rehash_password<HS>(pool: &PgPool, hash_service: &HS, user: &mut User, password: &str)
+    where
+        HS: PasswordHashService,
+    {
+        let password_hash = match hash_service.hash_password(password) {
+            Ok(password_hash) => password_hash,
+            Err(err) => {
+                tracing::warn!(
+                    target = "use_case::iam::rehash_password",
+                    user_id = %user.ident(),
+                    cause = %err,
+                );
+                return;
+            }
+        };
+
+        user.rehash_password(password_hash);
+        if let Err(err) = repository::update_user(pool, user).await {
+            tracing::warn!(
+                target = "use_case::iam::rehash_password",
+                user_id = %user.ident(),
+                cause = %err,
+            );
+        }
     }
 
     const AUTHENTICATION_TOKEN_EXPIRATION: Duration = Duration::from_secs(60 * 60 * 8);
+    const REFRESH_TOKEN_EXPIRATION: Duration = Duration::from_secs(60 * 60 * 24 * 30);
 
-    pub async fn authenticate_user<'dto, HS, TS>(
+    fn issue_user_token<TS, T>(
+        token_service: &TS,
+        id: Uuid,
+        expiration: Duration,
+        data: T,
+    ) -> String
+    where
+        TS: TokenEncryptionService,
+        T: serde::Serialize,
+    {
+        let payload = TokenPayload::new(
+            expiration,
+            token_service.issuer().clone(),
+            TokenSubject::User(id),
+            data,
+        );
+        Token::new(payload, token_service)
+            .expect("Expect to sign a user authentication token")
+            .into()
+    }
+
+    /// Credential without the attempted password, to be responded back in a error.
+    fn redacted<'dto>(credential: &UserCredential<'dto>) -> UserCredential<'dto> {
+        UserCredential {
+            password: "",
+            ..credential.clone()
+        }
+    }
+
+    /// Error of a credential with a unknown email.
+    ///
+    /// When concealed, the password is verified against a dummy hash, so the response
+    /// doesn't tell a unknown email apart from a wrong password, neither by its time.
+    fn unknown_email<'dto, HS>(
+        hash_service: &HS,
+        credential: UserCredential<'dto>,
+        conceal_existing_email: bool,
+    ) -> ApplicationError<UserCredential<'dto>>
+    where
+        HS: PasswordHashService,
+    {
+        if conceal_existing_email {
+            hash_service.verify_dummy_password(credential.password);
+            return AuthenticationError::InvalidCredential.into();
+        }
+
+        ValidationError::from_resource(
+            redacted(&credential),
+            vec![ValidationFieldError::new(
+                "base::email",
+                credential.email.into(),
+                JsonPointer::new(["email"]),
+                vec![ValidationErrorKind::NotFound],
+            )],
+        )
+        .into()
+    }
+
+    /// Authenticate a user by the credential.
+    ///
+    /// With `conceal_existing_email`, a unknown email is answered as a wrong password.
+    pub async fn authenticate_user<'dto, HS, TS, AS>(
         pool: &PgPool,
         hash_service: &HS,
         token_service: &TS,
+        audit: &AS,
+        origin: Option<IpAddr>,
         credential: UserCredential<'dto>,
+        conceal_existing_email: bool,
     ) -> Result<AuthenticateUserResponse, ApplicationError<UserCredential<'dto>>>
     where
         HS: PasswordHashService,
         TS: TokenEncryptionService,
+        AS: AuditSink,
     {
-        let user = repository::find_user_by_email(pool, credential.email.into())
-            .await?
-            .ok_or_else(|| {
-                ValidationError::from_resource(
-                    credential.clone(),
-                    vec![ValidationFieldError::new(
-                        "base::email",
-                        credential.email.into(),
-                        "/email".into(),
-                        vec![ValidationErrorKind::NotFound],
-                    )],
-                )
-            })?;
+        let email = credential
+            .email
+            .parse::<Email>()
+            .map_err(|err| ValidationError::from_resource(redacted(&credential), vec![err]))?;
+
+        let mut user = match repository::find_user_by_email(pool, email.as_str().into()).await? {
+            Some(user) => user,
+            None => {
+                audit
+                    .record(AuditEvent::new(
+                        AuditAction::LoginFailed,
+                        None,
+                        Some(email.as_str().into()),
+                        origin,
+                    ))
+                    .await;
+
+                return Err(unknown_email(
+                    hash_service,
+                    credential,
+                    conceal_existing_email,
+                ));
+            }
+        };
 
         if hash_service
             .verify_password(credential.password, user.password_hash())
             .is_err()
         {
+            audit
+                .record(AuditEvent::new(
+                    AuditAction::LoginFailed,
+                    Some(user.ident()),
+                    Some(email.as_str().into()),
+                    origin,
+                ))
+                .await;
+
             return Err(AuthenticationError::InvalidCredential.into());
         }
 
-        let payload = TokenPayload::new(
-            AUTHENTICATION_TOKEN_EXPIRATION,
-            TokenSubject::User(user.ident()),
-            (),
-        );
-        let token =
-            Token::new(payload, token_service).expect("Expect to sign a user authentication token");
+        if *user.deactivated() {
+            audit
+                .record(AuditEvent::new(
+                    AuditAction::LoginFailed,
+                    Some(user.ident()),
+                    Some(email.as_str().into()),
+                    origin,
+                ))
+                .await;
+
+            return Err(OperationError::AccountDeactivated.into());
+        }
+
+        if hash_service.needs_rehash(user.password_hash()) {
+            rehash_password(pool, hash_service, &mut user, credential.password).await;
+        }
+
+        audit
+            .record(AuditEvent::new(
+                AuditAction::LoginSucceeded,
+                Some(user.ident()),
+                Some(email.as_str().into()),
+                origin,
+            ))
+            .await;
 
         Ok(AuthenticateUserResponse {
+            token: issue_user_token(
+                token_service,
+                user.ident(),
+                AUTHENTICATION_TOKEN_EXPIRATION,
+                (),
+            ),
+            refresh_token: issue_user_token(
+                token_service,
+                user.ident(),
+                REFRESH_TOKEN_EXPIRATION,
+                TokenKind::Refresh,
+            ),
             user: user.into(),
-            token: token.into(),
         })
     }
 
-    pub async fn update_user<TS>(
+    /// Issue a new access token in exchange of a refresh token.
+    ///
+    /// The refresh token is not renewed, the user authenticates again once it expires.
+    pub async fn refresh_token<TS>(
         pool: &PgPool,
         token_service: &TS,
-        token: &str,
-        id: Uuid,
-        dto: UpdateUser,
-    ) -> Result<UserResponse, ApplicationError<UpdateUser>>
+        dto: RefreshTokenBody<'_>,
+    ) -> Result<AuthenticateUserResponse, ApplicationError<()>>
     where
         TS: TokenEncryptionService,
     {
-        let mut user = repository::find_user_by_id(pool, id)
+        let payload: TokenPayload<TokenKind> = token_service
+            .verify_token(dto.refresh_token)
+            .map_err(AuthenticationError::from)?;
+
+        let id = match payload.subject() {
+            TokenSubject::User(id) => *id,
+            TokenSubject::Public => return Err(ForbiddenError::AccessDenied.into()),
+        };
+
+        let user = repository::find_user_by_id(pool, id)
             .await?
             .ok_or_else(|| NotFoundError::from_resource::<UserResponse>(id))?;
 
+        if *user.deactivated() {
+            return Err(OperationError::AccountDeactivated.into());
+        }
+
+        Ok(AuthenticateUserResponse {
+            token: issue_user_token(token_service, id, AUTHENTICATION_TOKEN_EXPIRATION, ()),
+            refresh_token: dto.refresh_token.into(),
+            user: user.into(),
+        })
+    }
+
+    /// Decode the claims of a authentication token, for client debugging.
+    pub fn introspect_token<TS>(
+        token_service: &TS,
+        token: &str,
+    ) -> Result<TokenIntrospectionResponse, ApplicationError<()>>
+    where
+        TS: TokenEncryptionService,
+    {
+        // a expired token is inspected as well, to report it expired
         let payload: TokenPayload<()> = token_service
-            .verify_token(token)
+            .inspect_token(token)
             .map_err(AuthenticationError::from)?;
 
-        if *payload.subject() != TokenSubject::User(id) {
-            return Err(AuthenticationError::InvalidToken.into());
-        }
+        Ok(TokenIntrospectionResponse {
+            sub: payload.subject().clone(),
+            iss: payload.issuer().clone(),
+            aud: None,
+            iat: payload.issued_at(),
+            exp: payload.expiration(),
+            jti: None,
+            expired: payload.expired(),
+            revoked: false,
+        })
+    }
+
+    pub async fn update_user(
+        pool: &PgPool,
+        subject: &TokenSubject,
+        id: Uuid,
+        dto: UpdateUser,
+    ) -> Result<UserResponse, ApplicationError<UpdateUser>> {
+        // authorized before the query, not to disclose whether the user exists
+        authorize_user(subject, id)?;
+
+        let mut user = find_active_user(pool, id).await?;
 
-        user.update(dto.bio, dto.image_url);
+        let bio = match dto.bio.as_deref().map(Bio::new).transpose() {
+            Ok(bio) => bio,
+            Err(err) => return Err(ValidationError::from_resource(dto, vec![err]).into()),
+        };
+
+        user.update(bio, dto.image_url);
 
         repository::update_user(pool, &user).await?;
 
         Ok(user.into())
     }
+
+    pub async fn export_user(
+        pool: &PgPool,
+        subject: &TokenSubject,
+    ) -> Result<UserExportResponse, ApplicationError<()>> {
+        let id = match subject {
+            TokenSubject::User(id) => *id,
+            TokenSubject::Public => return Err(ForbiddenError::AccessDenied.into()),
+        };
+
+        let user = find_active_user(pool, id).await?;
+
+        let following = repository::find_following_usernames(pool, id).await?;
+        let followers = repository::find_follower_usernames(pool, id).await?;
+
+        Ok(UserExportResponse {
+            user: user.into(),
+            following,
+            followers,
+        })
+    }
+
+    pub async fn deactivate_user<AS>(
+        pool: &PgPool,
+        audit: &AS,
+        origin: Option<IpAddr>,
+        subject: &TokenSubject,
+        id: Uuid,
+    ) -> Result<UserResponse, ApplicationError<()>>
+    where
+        AS: AuditSink,
+    {
+        authorize_user(subject, id)?;
+
+        let mut user = find_active_user(pool, id).await?;
+
+        user.deactivate();
+        repository::update_user(pool, &user).await?;
+
+        audit
+            .record(AuditEvent::new(
+                AuditAction::AccountDeactivated,
+                Some(id),
+                Some(id.to_string()),
+                origin,
+            ))
+            .await;
+
+        Ok(user.into())
+    }
+
+    /// Reactivate a deactivated account.
+    ///
+    /// Since a deactivated user can not authenticate, the account credential is required.
+    pub async fn reactivate_user<'dto, HS>(
+        pool: &PgPool,
+        hash_service: &HS,
+        credential: UserCredential<'dto>,
+        conceal_existing_email: bool,
+    ) -> Result<UserResponse, ApplicationError<UserCredential<'dto>>>
+    where
+        HS: PasswordHashService,
+    {
+        let email = credential
+            .email
+            .parse::<Email>()
+            .map_err(|err| ValidationError::from_resource(redacted(&credential), vec![err]))?;
+
+        let mut user = match repository::find_user_by_email(pool, email.as_str().into()).await? {
+            Some(user) => user,
+            None => {
+                return Err(unknown_email(
+                    hash_service,
+                    credential,
+                    conceal_existing_email,
+                ))
+            }
+        };
+
+        if hash_service
+            .verify_password(credential.password, user.password_hash())
+            .is_err()
+        {
+            return Err(AuthenticationError::InvalidCredential.into());
+        }
+
+        if *user.deactivated() {
+            user.reactivate();
+            repository::update_user(pool, &user).await?;
+        }
+
+        Ok(user.into())
+    }
+}
+
+pub mod profile {
+    use std::{collections::HashSet, net::IpAddr};
+
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::{
+        app::resource::profile::{
+            FollowProfile, FollowingStatusQuery, FollowingStatusResponse, ProfileResponse,
+        },
+        domain::{
+            datatype::{
+                audit::{AuditAction, AuditEvent},
+                security::TokenSubject,
+                JsonPointer,
+            },
+            entity::{iam::User, profile::Follow, Entity},
+            service::AuditSink,
+        },
+        error::{
+            app::ApplicationError,
+            operation::OperationError,
+            resource::{NotFoundError, ValidationError, ValidationErrorKind, ValidationFieldError},
+            security::{AuthenticationError, ForbiddenError},
+        },
+        infra::database::repository,
+    };
+
+    /// Find the active user of a profile.
+    ///
+    /// Deactivated users have no public profile.
+    async fn find_profile_user<R>(
+        pool: &PgPool,
+        username: &str,
+    ) -> Result<User, ApplicationError<R>> {
+        match repository::find_user_by_username(pool, username.into()).await? {
+            Some(user) if !*user.deactivated() => Ok(user),
+            _ => Err(NotFoundError::from_resource_type::<ProfileResponse>().into()),
+        }
+    }
+
+    /// Active user acting on the profiles, public tokens don't represent any user.
+    async fn subject_user<R>(
+        pool: &PgPool,
+        subject: &TokenSubject,
+    ) -> Result<User, ApplicationError<R>> {
+        let id = match subject {
+            TokenSubject::User(id) => *id,
+            TokenSubject::Public => return Err(ForbiddenError::AccessDenied.into()),
+        };
+
+        match repository::find_user_by_id(pool, id).await? {
+            Some(user) if *user.deactivated() => Err(OperationError::AccountDeactivated.into()),
+            Some(user) => Ok(user),
+            // a valid token of a user no longer registered
+            None => Err(AuthenticationError::InvalidToken.into()),
+        }
+    }
+
+    fn profile(user: User, following: bool) -> ProfileResponse {
+        ProfileResponse {
+            following,
+            ..ProfileResponse::from(user)
+        }
+    }
+
+    /// Profiles of `users`, flagged by whether the `subject` follows them.
+    ///
+    /// The follows of the whole page are resolved in a single query.
+    async fn profiles<R>(
+        pool: &PgPool,
+        subject: Option<&TokenSubject>,
+        users: Vec<User>,
+    ) -> Result<Vec<ProfileResponse>, ApplicationError<R>> {
+        let following = match subject {
+            Some(TokenSubject::User(id)) => {
+                let ids: Vec<Uuid> = users.iter().map(|user| user.ident()).collect();
+                repository::following_ids(pool, *id, &ids).await?
+            }
+            _ => HashSet::new(),
+        };
+
+        Ok(users
+            .into_iter()
+            .map(|user| {
+                let following = following.contains(&user.ident());
+                profile(user, following)
+            })
+            .collect())
+    }
+
+    /// Find the profile of a user by username.
+    ///
+    /// With a `subject`, whether it follows the profile.
+    pub async fn get_profile(
+        pool: &PgPool,
+        subject: Option<&TokenSubject>,
+        username: &str,
+    ) -> Result<ProfileResponse, ApplicationError<()>> {
+        let user = find_profile_user(pool, username).await?;
+
+        let mut profiles = profiles(pool, subject, vec![user]).await?;
+        Ok(profiles.remove(0))
+    }
+
+    /// Profile of the authenticated user, addressed by the `me` alias.
+    pub async fn get_own_profile(
+        pool: &PgPool,
+        subject: &TokenSubject,
+    ) -> Result<ProfileResponse, ApplicationError<()>> {
+        let user = subject_user(pool, subject).await?;
+
+        Ok(profile(user, false))
+    }
+
+    /// Follow the profile of a user.
+    ///
+    /// Following a already followed profile succeeds without changes, also when the
+    /// follower reached the `follow_limit`.
+    pub async fn follow_user<'dto, AS>(
+        pool: &PgPool,
+        audit: &AS,
+        origin: Option<IpAddr>,
+        subject: &TokenSubject,
+        dto: FollowProfile<'dto>,
+        follow_limit: Option<u32>,
+    ) -> Result<ProfileResponse, ApplicationError<FollowProfile<'dto>>>
+    where
+        AS: AuditSink,
+    {
+        let follower_id = subject_user(pool, subject).await?.ident();
+        let user = find_profile_user(pool, dto.username).await?;
+
+        if user.ident() == follower_id {
+            return Err(ValidationError::from_resource(
+                dto.clone(),
+                vec![ValidationFieldError::new(
+                    "base::username",
+                    dto.username.into(),
+                    JsonPointer::new(["username"]),
+                    vec![ValidationErrorKind::Invalid],
+                )],
+            )
+            .into());
+        }
+
+        if let Some(limit) = follow_limit {
+            let followed = repository::following_ids(pool, follower_id, &[user.ident()])
+                .await?
+                .contains(&user.ident());
+            if !followed
+                && repository::count_following(pool, follower_id).await? >= u64::from(limit)
+            {
+                return Err(OperationError::FollowLimitReached.into());
+            }
+        }
+
+        let follow = Follow::new(Uuid::new_v4(), follower_id, user.ident());
+        if repository::insert_follow(pool, &follow).await? {
+            audit
+                .record(AuditEvent::new(
+                    AuditAction::Followed,
+                    Some(follower_id),
+                    Some(user.ident().to_string()),
+                    origin,
+                ))
+                .await;
+        }
+
+        Ok(profile(user, true))
+    }
+
+    /// Whether the authenticated user follows each of the profiles, by user id.
+    ///
+    /// A id of no profile is answered as not followed.
+    pub async fn following_status(
+        pool: &PgPool,
+        subject: &TokenSubject,
+        dto: FollowingStatusQuery,
+    ) -> Result<FollowingStatusResponse, ApplicationError<FollowingStatusQuery>> {
+        let follower_id = subject_user(pool, subject).await?.ident();
+
+        if dto.ids.len() > FollowingStatusQuery::MAX_IDS {
+            let err = ValidationFieldError::from_resource::<FollowingStatusQuery>(
+                dto.ids.len().to_string(),
+                JsonPointer::new(["ids"]),
+                vec![ValidationErrorKind::MaxLength(
+                    FollowingStatusQuery::MAX_IDS as u64,
+                )],
+            );
+            return Err(ValidationError::from_resource(dto, vec![err]).into());
+        }
+
+        let following = repository::following_ids(pool, follower_id, &dto.ids).await?;
+
+        Ok(FollowingStatusResponse {
+            following: dto
+                .ids
+                .into_iter()
+                .map(|id| (id, following.contains(&id)))
+                .collect(),
+        })
+    }
+
+    /// Unfollow the profile of a user, also succeeding when not followed.
+    pub async fn unfollow_user<'dto, AS>(
+        pool: &PgPool,
+        audit: &AS,
+        origin: Option<IpAddr>,
+        subject: &TokenSubject,
+        dto: FollowProfile<'dto>,
+    ) -> Result<(), ApplicationError<FollowProfile<'dto>>>
+    where
+        AS: AuditSink,
+    {
+        let follower_id = subject_user(pool, subject).await?.ident();
+        let user = find_profile_user(pool, dto.username).await?;
+
+        if repository::delete_follow(pool, follower_id, user.ident()).await? {
+            audit
+                .record(AuditEvent::new(
+                    AuditAction::Unfollowed,
+                    Some(follower_id),
+                    Some(user.ident().to_string()),
+                    origin,
+                ))
+                .await;
+        }
+
+        Ok(())
+    }
+}
+
+pub mod blog {
+    use crate::{
+        app::{query::article::SlugifyParams, resource::article::SlugPreviewResponse},
+        domain::datatype::{blog::Slug, JsonPointer},
+        error::{
+            app::ApplicationError,
+            resource::{ValidationError, ValidationErrorKind, ValidationFieldError},
+        },
+    };
+
+    /// Preview the slug of a article title, without checking if it is already in use.
+    pub fn preview_slug(
+        params: SlugifyParams,
+    ) -> Result<SlugPreviewResponse, ApplicationError<SlugifyParams>> {
+        let result = match params.title.as_deref() {
+            Some(title) => Slug::from_title(title).map_err(|mut err| {
+                err.path = JsonPointer::new(["title"]);
+                err
+            }),
+            None => Err(ValidationFieldError::from_resource::<Slug>(
+                String::new(),
+                JsonPointer::new(["title"]),
+                vec![ValidationErrorKind::Required],
+            )),
+        };
+
+        match result {
+            Ok(slug) => Ok(SlugPreviewResponse { slug: slug.into() }),
+            Err(err) => Err(ValidationError::from_resource(params, vec![err]).into()),
+        }
+    }
 }
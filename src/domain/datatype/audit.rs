@@ -0,0 +1,73 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use uuid::Uuid;
+
+/// Security relevant action recorded in the audit trail.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    /// User authenticated with valid credentials.
+    #[display(fmt = "login_succeeded")]
+    LoginSucceeded,
+    /// Attempt to authenticate with invalid credentials.
+    #[display(fmt = "login_failed")]
+    LoginFailed,
+    /// User followed a profile.
+    #[display(fmt = "followed")]
+    Followed,
+    /// User unfollowed a profile.
+    #[display(fmt = "unfollowed")]
+    Unfollowed,
+    /// User deactivated the account.
+    #[display(fmt = "account_deactivated")]
+    AccountDeactivated,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LoginSucceeded => "login_succeeded",
+            AuditAction::LoginFailed => "login_failed",
+            AuditAction::Followed => "followed",
+            AuditAction::Unfollowed => "unfollowed",
+            AuditAction::AccountDeactivated => "account_deactivated",
+        }
+    }
+}
+
+/// Audit trail record.
+///
+/// Must never carry credentials or secrets, only who did what to whom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    /// Event id
+    pub id: Uuid,
+    /// Time of the action
+    pub time: DateTime<Utc>,
+    pub action: AuditAction,
+    /// User that performed the action, when known
+    pub actor: Option<Uuid>,
+    /// Subject of the action (e.g. the attempted email of a failed login)
+    pub target: Option<String>,
+    /// Address of the client performing the action
+    pub ip: Option<IpAddr>,
+}
+
+impl AuditEvent {
+    pub fn new(
+        action: AuditAction,
+        actor: Option<Uuid>,
+        target: Option<String>,
+        ip: Option<IpAddr>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            time: Utc::now(),
+            action,
+            actor,
+            target,
+            ip,
+        }
+    }
+}
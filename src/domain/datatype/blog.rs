@@ -0,0 +1,659 @@
+use std::fmt;
+use std::str::FromStr;
+
+use derive_more::Display;
+
+use super::JsonPointer;
+use crate::base::ResourceID;
+use crate::error::resource::{ValidationErrorKind, ValidationFieldError};
+
+/// Article slug
+///
+/// Url friendly identifier, containing only lowercase ascii letters, digits
+/// and single hyphens between words.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Slug(String);
+
+impl Slug {
+    pub const SEPARATOR: char = '-';
+
+    /// Generate the slug of a article title.
+    ///
+    /// Non ascii characters are transliterated, as "Café" into "cafe", and whitespace
+    /// and punctuation separate words. A title without any letter or digit has no slug.
+    pub fn from_title(title: &str) -> Result<Self, ValidationFieldError> {
+        let ascii = deunicode::deunicode(title);
+        let mut slug = String::with_capacity(ascii.len());
+        let mut separated = false;
+
+        for ch in ascii.chars().flat_map(char::to_lowercase) {
+            if ch.is_ascii_alphanumeric() {
+                if separated && !slug.is_empty() {
+                    slug.push(Self::SEPARATOR);
+                }
+                separated = false;
+                slug.push(ch);
+            } else if ch.is_whitespace() || ch.is_ascii_punctuation() {
+                separated = true;
+            }
+        }
+
+        if slug.is_empty() {
+            return Err(ValidationFieldError::from_resource::<Self>(
+                title.into(),
+                JsonPointer::root(),
+                vec![ValidationErrorKind::Invalid],
+            ));
+        }
+
+        Ok(Self(slug))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ResourceID for Slug {
+    fn resource_id() -> &'static str {
+        "blog::slug"
+    }
+}
+
+impl fmt::Display for Slug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Slug> for String {
+    fn from(slug: Slug) -> Self {
+        slug.0
+    }
+}
+
+/// Storage format of the article body.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyFormat {
+    /// Raw markdown, rendered by the clients.
+    #[default]
+    #[display(fmt = "markdown")]
+    Markdown,
+    /// Markdown with only allowlisted HTML, safe to render on the server.
+    #[display(fmt = "sanitized")]
+    Sanitized,
+}
+
+impl ResourceID for BodyFormat {
+    fn resource_id() -> &'static str {
+        "blog::body_format"
+    }
+}
+
+impl FromStr for BodyFormat {
+    type Err = ValidationFieldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" => Ok(Self::Markdown),
+            "sanitized" => Ok(Self::Sanitized),
+            _ => Err(ValidationFieldError::from_resource::<Self>(
+                s.into(),
+                JsonPointer::root(),
+                vec![ValidationErrorKind::UnknownVariant],
+            )),
+        }
+    }
+}
+
+/// Article body
+///
+/// Markdown content, validated according to its [`BodyFormat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticleBody(String);
+
+impl ArticleBody {
+    /// HTML tags allowed in a sanitized body.
+    pub const ALLOWED_TAGS: &'static [&'static str] = &[
+        "a",
+        "b",
+        "blockquote",
+        "br",
+        "code",
+        "del",
+        "em",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "hr",
+        "i",
+        "img",
+        "li",
+        "ol",
+        "p",
+        "pre",
+        "strong",
+        "sub",
+        "sup",
+        "table",
+        "tbody",
+        "td",
+        "th",
+        "thead",
+        "tr",
+        "ul",
+    ];
+
+    pub fn new(body: &str, format: BodyFormat) -> Result<Self, ValidationFieldError> {
+        if body.trim().is_empty() {
+            return Err(ValidationFieldError::from_resource::<Self>(
+                body.into(),
+                JsonPointer::root(),
+                vec![ValidationErrorKind::Required],
+            ));
+        }
+
+        if format == BodyFormat::Sanitized {
+            let kinds = Self::unsafe_html(body);
+            if !kinds.is_empty() {
+                return Err(ValidationFieldError::from_resource::<Self>(
+                    body.into(),
+                    JsonPointer::root(),
+                    kinds,
+                ));
+            }
+        }
+
+        Ok(Self(body.into()))
+    }
+
+    /// Find the HTML constructs not allowed in a sanitized body.
+    ///
+    /// Only tags out of [`Self::ALLOWED_TAGS`], event handler attributes and
+    /// `javascript:` urls, in attributes, autolinks or markdown links, are rejected,
+    /// the remaining markdown is kept as is.
+    fn unsafe_html(body: &str) -> Vec<ValidationErrorKind> {
+        let mut kinds = Vec::new();
+
+        let mut rest = body;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+            let closing = rest.starts_with('/');
+            let tag = rest.strip_prefix('/').unwrap_or(rest);
+            let name_len = tag
+                .find(|ch: char| !ch.is_ascii_alphanumeric())
+                .unwrap_or(tag.len());
+            let (name, tail) = tag.split_at(name_len);
+
+            if !closing && !name.is_empty() && tail.starts_with(':') {
+                let url = &rest[..rest.find('>').unwrap_or(rest.len())];
+                if Self::is_script_url(url) {
+                    kinds.push(ValidationErrorKind::NegativePattern("javascript:".into()));
+                }
+                continue;
+            }
+
+            // not a tag, as a comparison
+            let is_tag = !name.is_empty()
+                && tail.starts_with(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/');
+            if !is_tag {
+                continue;
+            }
+
+            let name = name.to_ascii_lowercase();
+            if !Self::ALLOWED_TAGS.contains(&name.as_str()) {
+                kinds.push(ValidationErrorKind::NegativePattern(format!("<{name}")));
+                continue;
+            }
+
+            let (attributes, tag_len) = Self::tag_attributes(tail);
+            if attributes.iter().any(|(name, _)| name.starts_with("on")) {
+                kinds.push(ValidationErrorKind::NegativePattern("on*=".into()));
+            }
+            if attributes
+                .iter()
+                .any(|(_, value)| Self::is_script_url(value))
+            {
+                kinds.push(ValidationErrorKind::NegativePattern("javascript:".into()));
+            }
+            rest = &tail[tag_len..];
+        }
+
+        for target in Self::markdown_link_targets(body) {
+            if Self::is_script_url(target) {
+                kinds.push(ValidationErrorKind::NegativePattern("javascript:".into()));
+            }
+        }
+
+        kinds
+    }
+
+    /// Parse the attributes of a tag, up to the `>` out of a quoted value.
+    ///
+    /// Returns the lowercase attribute names with their raw values, and the length of the tag.
+    fn tag_attributes(tail: &str) -> (Vec<(String, &str)>, usize) {
+        let bytes = tail.as_bytes();
+        let is_separator = |byte: u8| byte.is_ascii_whitespace() || byte == b'/';
+        let mut attributes = Vec::new();
+        let mut i = 0;
+
+        loop {
+            while i < bytes.len() && is_separator(bytes[i]) {
+                i += 1;
+            }
+            match bytes.get(i) {
+                None => return (attributes, bytes.len()),
+                Some(b'>') => return (attributes, i + 1),
+                Some(_) => {}
+            }
+
+            let name_start = i;
+            while i < bytes.len() && !is_separator(bytes[i]) && !matches!(bytes[i], b'>' | b'=') {
+                i += 1;
+            }
+            let name = tail[name_start..i].to_ascii_lowercase();
+
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let mut value = "";
+            if bytes.get(i) == Some(&b'=') {
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                match bytes.get(i) {
+                    Some(&quote) if quote == b'"' || quote == b'\'' => {
+                        let start = i + 1;
+                        let end = tail[start..]
+                            .find(quote as char)
+                            .map_or(bytes.len(), |len| start + len);
+                        value = &tail[start..end];
+                        i = (end + 1).min(bytes.len());
+                    }
+                    _ => {
+                        let start = i;
+                        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>'
+                        {
+                            i += 1;
+                        }
+                        value = &tail[start..i];
+                    }
+                }
+            }
+
+            attributes.push((name, value));
+        }
+    }
+
+    /// Destinations of the markdown inline links and images, and of the link reference definitions.
+    fn markdown_link_targets(body: &str) -> impl Iterator<Item = &str> {
+        let inline = body
+            .match_indices("](")
+            .map(move |(start, _)| &body[start + 2..]);
+        let definitions = body.lines().filter_map(|line| {
+            let line = line.trim_start();
+            let label_len = line.strip_prefix('[')?.find("]:")?;
+            Some(&line[label_len + 3..])
+        });
+
+        inline.chain(definitions).map(|target| {
+            let target = target.trim_start();
+            match target.strip_prefix('<') {
+                Some(target) => &target[..target.find('>').unwrap_or(target.len())],
+                None => {
+                    let len = target
+                        .find(|ch: char| ch.is_whitespace() || ch == ')')
+                        .unwrap_or(target.len());
+                    &target[..len]
+                }
+            }
+        })
+    }
+
+    /// Whether a url runs a script, once its character references are decoded.
+    fn is_script_url(url: &str) -> bool {
+        // the browsers ignore whitespace and control characters in the scheme,
+        // and markdown drops the backslash escapes
+        let url: String = Self::decode_char_references(url)
+            .chars()
+            .filter(|ch| !ch.is_whitespace() && !ch.is_control() && *ch != '\\')
+            .collect();
+        url.to_ascii_lowercase().starts_with("javascript:")
+    }
+
+    /// Decode the HTML character references, as `&#115;`, `&#x73;` and `&colon;`.
+    ///
+    /// The terminating `;` is optional, as the browsers are lenient with it.
+    fn decode_char_references(value: &str) -> String {
+        let mut decoded = String::with_capacity(value.len());
+
+        let mut rest = value;
+        while let Some(start) = rest.find('&') {
+            decoded.push_str(&rest[..start]);
+            rest = &rest[start + 1..];
+
+            let (radix, digits) = match rest.strip_prefix('#') {
+                Some(num) => match num.strip_prefix(|ch| ch == 'x' || ch == 'X') {
+                    Some(hex) => (16, hex),
+                    None => (10, num),
+                },
+                None => (0, rest),
+            };
+            let len = digits
+                .find(|ch: char| !ch.is_digit(if radix == 0 { 36 } else { radix }))
+                .unwrap_or(digits.len());
+            let reference = &digits[..len];
+
+            let ch = match radix {
+                0 => match reference {
+                    "colon" => Some(':'),
+                    "Tab" => Some('\t'),
+                    "NewLine" => Some('\n'),
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "lpar" => Some('('),
+                    "rpar" => Some(')'),
+                    "sol" => Some('/'),
+                    _ => None,
+                },
+                radix => u32::from_str_radix(reference, radix)
+                    .ok()
+                    .and_then(char::from_u32),
+            };
+
+            match ch {
+                Some(ch) => {
+                    decoded.push(ch);
+                    rest = &digits[len..];
+                    rest = rest.strip_prefix(';').unwrap_or(rest);
+                }
+                None => decoded.push('&'),
+            }
+        }
+        decoded.push_str(rest);
+
+        decoded
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ResourceID for ArticleBody {
+    fn resource_id() -> &'static str {
+        "blog::article_body"
+    }
+}
+
+impl From<ArticleBody> for String {
+    fn from(body: ArticleBody) -> Self {
+        body.0
+    }
+}
+
+#[cfg(test)]
+mod article_body_test {
+    use pretty_assertions::assert_eq;
+
+    use super::{ArticleBody, BodyFormat};
+    use crate::error::resource::ValidationErrorKind;
+
+    #[test]
+    fn keep_raw_markdown() {
+        let body = "# Title\n\n<script>alert('xss')</script>";
+        let parsed = ArticleBody::new(body, BodyFormat::Markdown)
+            .expect("Expect a markdown body to be stored raw");
+        assert_eq!(parsed.as_str(), body);
+    }
+
+    #[test]
+    fn reject_script_when_sanitized() {
+        let body = "# Title\n\n<script>alert('xss')</script>";
+        let err = ArticleBody::new(body, BodyFormat::Sanitized)
+            .expect_err("Expect to reject a script tag");
+        assert_eq!(
+            err.kinds,
+            vec![ValidationErrorKind::NegativePattern("<script".into()); 2]
+        );
+
+        let err = ArticleBody::new("<SCRIPT src=\"x.js\"></SCRIPT>", BodyFormat::Sanitized)
+            .expect_err("Expect to reject a uppercase script tag");
+        assert_eq!(
+            err.kinds,
+            vec![ValidationErrorKind::NegativePattern("<script".into()); 2]
+        );
+    }
+
+    #[test]
+    fn reject_unsafe_attributes_when_sanitized() {
+        let err = ArticleBody::new("<img src=x onerror=alert(1)>", BodyFormat::Sanitized)
+            .expect_err("Expect to reject a event handler attribute");
+        assert_eq!(
+            err.kinds,
+            vec![ValidationErrorKind::NegativePattern("on*=".into())]
+        );
+
+        let err = ArticleBody::new(
+            "<a href=\"javascript:alert(1)\">link</a>",
+            BodyFormat::Sanitized,
+        )
+        .expect_err("Expect to reject a javascript url");
+        assert_eq!(
+            err.kinds,
+            vec![ValidationErrorKind::NegativePattern("javascript:".into())]
+        );
+    }
+
+    #[test]
+    fn reject_event_handler_past_quoted_values() {
+        let bodies = [
+            "<img alt=\">\" onerror=alert(1)>",
+            "<a href=\"x\"onclick=alert(1)>link</a>",
+        ];
+
+        for body in bodies {
+            let err = ArticleBody::new(body, BodyFormat::Sanitized)
+                .expect_err("Expect to reject a event handler attribute");
+            assert_eq!(
+                err.kinds,
+                vec![ValidationErrorKind::NegativePattern("on*=".into())],
+                "{body}"
+            );
+        }
+    }
+
+    #[test]
+    fn reject_encoded_and_markdown_javascript_urls() {
+        let bodies = [
+            "<a href=\"java&#115;cript:alert(1)\">link</a>",
+            "<a href=\"&#x6A;ava&Tab;script&colon;alert(1)\">link</a>",
+            "[x](java&#115;cript:alert(1))",
+            "[x](javascript:alert(1))",
+            "![x](JavaScript:alert(1))",
+            "[x]: javascript:alert(1)",
+            "<javascript:alert(1)>",
+        ];
+
+        for body in bodies {
+            let err = ArticleBody::new(body, BodyFormat::Sanitized)
+                .expect_err("Expect to reject a javascript url");
+            assert_eq!(
+                err.kinds,
+                vec![ValidationErrorKind::NegativePattern("javascript:".into())],
+                "{body}"
+            );
+        }
+    }
+
+    #[test]
+    fn allow_safe_markdown_when_sanitized() {
+        let bodies = [
+            "Some **bold** text, `a < b` and <https://example.com>",
+            "<p>A <strong>allowed</strong> tag<br/></p>",
+            "<a href=\"https://example.com\">link</a>",
+            "<img alt=\"a > b\" src=\"https://example.com/a.png\">",
+            "[link](https://example.com) and [ref]: https://example.com",
+            "Fish &amp; chips, &#38; [a link](https://example.com/?a=1&b=2)",
+        ];
+
+        for body in bodies {
+            ArticleBody::new(body, BodyFormat::Sanitized)
+                .expect("Expect to allow a safe markdown body");
+        }
+    }
+
+    #[test]
+    fn require_content() {
+        let err = ArticleBody::new("  \n", BodyFormat::Markdown).expect_err("Expect a empty body");
+        assert_eq!(err.kinds, vec![ValidationErrorKind::Required]);
+    }
+
+    #[test]
+    fn parse_body_format() {
+        assert_eq!(
+            "markdown".parse::<BodyFormat>().ok(),
+            Some(BodyFormat::Markdown)
+        );
+        assert_eq!(
+            "sanitized".parse::<BodyFormat>().ok(),
+            Some(BodyFormat::Sanitized)
+        );
+        assert!("html".parse::<BodyFormat>().is_err());
+    }
+}
+
+/// Reaction of a comment vote.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reaction {
+    #[display(fmt = "up")]
+    Up,
+    #[display(fmt = "down")]
+    Down,
+}
+
+impl Reaction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reaction::Up => "up",
+            Reaction::Down => "down",
+        }
+    }
+}
+
+impl ResourceID for Reaction {
+    fn resource_id() -> &'static str {
+        "blog::reaction"
+    }
+}
+
+impl FromStr for Reaction {
+    type Err = ValidationFieldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            _ => Err(ValidationFieldError::from_resource::<Self>(
+                s.into(),
+                JsonPointer::root(),
+                vec![ValidationErrorKind::UnknownVariant],
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod reaction_test {
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+
+    use super::Reaction;
+    use crate::error::resource::ValidationErrorKind;
+
+    #[test]
+    fn parse_and_display() {
+        for reaction in [Reaction::Up, Reaction::Down] {
+            let parsed =
+                Reaction::from_str(reaction.as_str()).expect("Expect to parse a valid reaction");
+            assert_eq!(parsed, reaction);
+            assert_eq!(parsed.to_string(), reaction.as_str());
+        }
+    }
+
+    #[test]
+    fn reject_unknown_reaction() {
+        for value in ["", "Up", "like", "👍"] {
+            let err = Reaction::from_str(value).expect_err("Expect to reject a unknown reaction");
+            assert_eq!(err.value, value);
+            assert_eq!(err.kinds, vec![ValidationErrorKind::UnknownVariant]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod slug_test {
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    use super::Slug;
+
+    #[test]
+    fn slug_from_title() {
+        let titles = [
+            ("How to train your dragon", "how-to-train-your-dragon"),
+            ("  Rust: 2021 Edition!  ", "rust-2021-edition"),
+            ("Don't--repeat__yourself", "don-t-repeat-yourself"),
+            ("Café com leite", "cafe-com-leite"),
+            ("Crème brûlée", "creme-brulee"),
+            ("Rust — the «good» parts", "rust-the-good-parts"),
+            ("already-a-slug", "already-a-slug"),
+        ];
+
+        for (title, expected) in titles {
+            let slug = Slug::from_title(title).expect("Expect a title with a slug");
+            assert_eq!(slug.as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn reject_title_without_slug() {
+        for title in ["", "   ", "!?", "—…"] {
+            assert!(
+                Slug::from_title(title).is_err(),
+                "Expect `{title}` to have no slug"
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn slug_is_idempotent(title in any::<String>()) {
+            if let Ok(slug) = Slug::from_title(&title) {
+                let again = Slug::from_title(slug.as_str()).expect("Expect a slug to have a slug");
+                prop_assert_eq!(again, slug);
+            }
+        }
+
+        #[test]
+        fn slug_has_only_single_inner_hyphens(title in any::<String>()) {
+            if let Ok(slug) = Slug::from_title(&title) {
+                let slug = slug.as_str();
+                prop_assert!(slug
+                    .chars()
+                    .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == Slug::SEPARATOR));
+                prop_assert!(!slug.starts_with(Slug::SEPARATOR));
+                prop_assert!(!slug.ends_with(Slug::SEPARATOR));
+                prop_assert!(!slug.contains("--"));
+            }
+        }
+    }
+}
@@ -1,31 +1,484 @@
+pub mod audit;
+pub mod blog;
+pub mod numeric;
 pub mod security;
 
-use crate::error::resource::ValidationFieldError;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::base::ResourceID;
+use crate::error::resource::{ValidationErrorKind, ValidationFieldError};
 
 // ### JsonPointer
 
+/// JSON pointer described in [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct JsonPointer {
     segments: Vec<Box<str>>,
 }
 
 impl JsonPointer {
+    pub const PATTERN: &'static str = "^(/([^~/]|~[01])*)*$";
+
+    /// Pointer to the whole document.
+    pub fn root() -> Self {
+        Self {
+            segments: Vec::new(),
+        }
+    }
+
+    /// Pointer of the unescaped reference tokens.
+    pub fn new<'a, I>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        Self {
+            segments: segments.into_iter().map(Box::from).collect(),
+        }
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().map(AsRef::as_ref)
+    }
+
+    /// Nest the pointer under `prefix`.
+    pub fn nested(mut self, prefix: &JsonPointer) -> Self {
+        self.segments.splice(0..0, prefix.segments.iter().cloned());
+        self
+    }
+
     // fn as_uri_fragment(&self) -> String {}
 }
 
+impl ResourceID for JsonPointer {
+    fn resource_id() -> &'static str {
+        "base::json_pointer"
+    }
+}
+
 impl std::str::FromStr for JsonPointer {
     type Err = ValidationFieldError;
 
-    fn from_str(_: &str) -> Result<Self, Self::Err> {
-        todo!("https://www.rfc-editor.org/rfc/rfc6901#section-4")
+    /// Parse the JSON string representation of a pointer.
+    ///
+    /// See <https://www.rfc-editor.org/rfc/rfc6901#section-4>
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            ValidationFieldError::from_resource::<Self>(
+                s.into(),
+                JsonPointer::root(),
+                vec![ValidationErrorKind::Pattern(Self::PATTERN.into())],
+            )
+        };
+
+        if s.is_empty() {
+            return Ok(Self::root());
+        }
+
+        let tokens = s.strip_prefix('/').ok_or_else(invalid)?;
+        let mut segments = Vec::new();
+        for token in tokens.split('/') {
+            let mut segment = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(ch) = chars.next() {
+                match ch {
+                    '~' => match chars.next() {
+                        Some('0') => segment.push('~'),
+                        Some('1') => segment.push('/'),
+                        _ => return Err(invalid()),
+                    },
+                    ch => segment.push(ch),
+                }
+            }
+            segments.push(segment.into_boxed_str());
+        }
+
+        Ok(Self { segments })
     }
 }
 
 impl std::fmt::Display for JsonPointer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for segment in self.segments.iter() {
-            write!(f, "/{}", segment)?;
+            write!(f, "/{}", segment.replace('~', "~0").replace('/', "~1"))?;
         }
         Ok(())
     }
 }
+
+impl PartialEq<&str> for JsonPointer {
+    fn eq(&self, other: &&str) -> bool {
+        self.to_string() == *other
+    }
+}
+
+impl serde::Serialize for JsonPointer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for JsonPointer {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod json_pointer_test {
+    use pretty_assertions::assert_eq;
+
+    use super::JsonPointer;
+
+    #[test]
+    fn parse_and_display() {
+        for pointer in ["", "/user", "/user/email", "/tags/0", "/a~1b/m~0n", "/"] {
+            let parsed: JsonPointer = pointer.parse().expect("Expect a valid json pointer");
+            assert_eq!(parsed.to_string(), pointer);
+        }
+
+        let parsed: JsonPointer = "/a~1b/m~0n".parse().expect("Expect a valid json pointer");
+        assert_eq!(parsed.segments().collect::<Vec<_>>(), vec!["a/b", "m~n"]);
+    }
+
+    #[test]
+    fn reject_invalid_pointer() {
+        for pointer in ["user", "/user~", "/user~2"] {
+            assert!(
+                pointer.parse::<JsonPointer>().is_err(),
+                "Expect to reject `{pointer}`"
+            );
+        }
+    }
+
+    #[test]
+    fn nest_pointer() {
+        let prefix = JsonPointer::new(["user"]);
+        assert_eq!(JsonPointer::new(["email"]).nested(&prefix), "/user/email");
+        assert_eq!(JsonPointer::root().nested(&prefix), "/user");
+    }
+}
+
+// ### Bio
+
+/// Profile biography.
+///
+/// Control characters, as NUL that Postgres `TEXT` can't store, are rejected,
+/// except the line feed, carriage return and tab of a multiline text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bio(String);
+
+impl Bio {
+    /// Maximum length in characters.
+    pub const MAX_LENGTH: u64 = 1024;
+
+    pub fn new(bio: &str) -> Result<Self, ValidationFieldError> {
+        let mut kinds = Vec::new();
+
+        if bio.chars().count() as u64 > Self::MAX_LENGTH {
+            kinds.push(ValidationErrorKind::MaxLength(Self::MAX_LENGTH));
+        }
+
+        if bio
+            .chars()
+            .any(|ch| ch.is_control() && !matches!(ch, '\n' | '\r' | '\t'))
+        {
+            kinds.push(ValidationErrorKind::NegativePattern("\\p{Cc}".into()));
+        }
+
+        if !kinds.is_empty() {
+            return Err(ValidationFieldError::from_resource::<Self>(
+                bio.into(),
+                JsonPointer::new(["bio"]),
+                kinds,
+            ));
+        }
+
+        Ok(Self(bio.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ResourceID for Bio {
+    fn resource_id() -> &'static str {
+        "base::bio"
+    }
+}
+
+impl From<Bio> for String {
+    fn from(bio: Bio) -> Self {
+        bio.0
+    }
+}
+
+#[cfg(test)]
+mod bio_test {
+    use pretty_assertions::assert_eq;
+
+    use super::Bio;
+    use crate::error::resource::ValidationErrorKind;
+
+    #[test]
+    fn accept_multiline_bio() {
+        let bio = "Writer.\r\n\tReader.";
+        assert_eq!(Bio::new(bio).expect("Expect a valid bio").as_str(), bio);
+    }
+
+    #[test]
+    fn reject_over_long_bio() {
+        let max = "b".repeat(Bio::MAX_LENGTH as usize);
+        assert!(Bio::new(&max).is_ok());
+
+        let err = Bio::new(&format!("{max}b")).expect_err("Expect a over long bio to fail");
+        assert_eq!(err.path, "/bio");
+        assert_eq!(
+            err.kinds,
+            vec![ValidationErrorKind::MaxLength(Bio::MAX_LENGTH)]
+        );
+    }
+
+    #[test]
+    fn reject_control_characters() {
+        for bio in ["null\0byte", "bell\u{7}", "escape\u{1b}[31m"] {
+            let err = Bio::new(bio).expect_err("Expect a control character to fail");
+            assert_eq!(err.path, "/bio");
+            assert_eq!(
+                err.kinds,
+                vec![ValidationErrorKind::NegativePattern("\\p{Cc}".into())]
+            );
+        }
+    }
+}
+
+// ### Username
+
+/// Public handle of a user, in the profile URL.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Username(String);
+
+impl Username {
+    /// Minimum length in characters.
+    pub const MIN_LENGTH: u64 = 4;
+    /// Maximum length in characters.
+    pub const MAX_LENGTH: u64 = 32;
+    pub const PATTERN: &'static str = "^[A-Za-z0-9_-]+$";
+
+    pub fn new(username: &str) -> Result<Self, ValidationFieldError> {
+        let mut kinds = Vec::new();
+
+        let length = username.chars().count() as u64;
+        if length < Self::MIN_LENGTH {
+            kinds.push(ValidationErrorKind::MinLength(Self::MIN_LENGTH));
+        } else if length > Self::MAX_LENGTH {
+            kinds.push(ValidationErrorKind::MaxLength(Self::MAX_LENGTH));
+        }
+
+        if username
+            .chars()
+            .any(|ch| !ch.is_ascii_alphanumeric() && !matches!(ch, '_' | '-'))
+        {
+            kinds.push(ValidationErrorKind::Pattern(Self::PATTERN.into()));
+        }
+
+        if !kinds.is_empty() {
+            return Err(ValidationFieldError::from_resource::<Self>(
+                username.into(),
+                JsonPointer::new(["username"]),
+                kinds,
+            ));
+        }
+
+        Ok(Self(username.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ResourceID for Username {
+    fn resource_id() -> &'static str {
+        "base::username"
+    }
+}
+
+impl From<Username> for String {
+    fn from(username: Username) -> Self {
+        username.0
+    }
+}
+
+#[cfg(test)]
+mod username_test {
+    use pretty_assertions::assert_eq;
+
+    use super::Username;
+    use crate::error::resource::ValidationErrorKind;
+
+    #[test]
+    fn accept_valid_usernames() {
+        for username in ["user", "User_12345", "first-last", &"u".repeat(32)] {
+            let parsed = Username::new(username).expect("Expect a valid username");
+            assert_eq!(parsed.as_str(), username);
+        }
+    }
+
+    #[test]
+    fn reject_too_short_username() {
+        for username in ["", "usr"] {
+            let err = Username::new(username).expect_err("Expect a short username to fail");
+            assert_eq!(err.path, "/username");
+            assert_eq!(
+                err.kinds,
+                vec![ValidationErrorKind::MinLength(Username::MIN_LENGTH)]
+            );
+        }
+    }
+
+    #[test]
+    fn reject_too_long_username() {
+        let username = "u".repeat(Username::MAX_LENGTH as usize + 1);
+        let err = Username::new(&username).expect_err("Expect a long username to fail");
+        assert_eq!(err.path, "/username");
+        assert_eq!(
+            err.kinds,
+            vec![ValidationErrorKind::MaxLength(Username::MAX_LENGTH)]
+        );
+    }
+
+    #[test]
+    fn reject_illegal_characters() {
+        for username in ["user name", "user@name", "user.name", "usér", "user\0name"] {
+            let err = Username::new(username).expect_err("Expect a illegal character to fail");
+            assert_eq!(err.path, "/username");
+            assert_eq!(
+                err.kinds,
+                vec![ValidationErrorKind::Pattern(Username::PATTERN.into())]
+            );
+        }
+    }
+}
+
+// ### Email
+
+/// Email address, normalized to lowercase.
+///
+/// Only the dot-atom subset of [RFC 5322](https://www.rfc-editor.org/rfc/rfc5322#section-3.4.1)
+/// is accepted, without quoted local parts, comments or domain literals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Email(String);
+
+impl Email {
+    pub const PATTERN: &'static str = r"^[a-z0-9!#$%&'*+/=?^_`{|}~-]+(\.[a-z0-9!#$%&'*+/=?^_`{|}~-]+)*@[a-z0-9]([a-z0-9-]*[a-z0-9])?(\.[a-z0-9]([a-z0-9-]*[a-z0-9])?)+$";
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for Email {
+    type Err = ValidationFieldError;
+
+    fn from_str(email: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref PATTERN: Regex =
+                Regex::new(Email::PATTERN).expect("Expect a valid email regex");
+        }
+
+        let email = email.to_lowercase();
+        if !PATTERN.is_match(&email) {
+            return Err(ValidationFieldError::from_resource::<Self>(
+                email,
+                JsonPointer::new(["email"]),
+                vec![ValidationErrorKind::Pattern(Self::PATTERN.into())],
+            ));
+        }
+
+        Ok(Self(email))
+    }
+}
+
+impl ResourceID for Email {
+    fn resource_id() -> &'static str {
+        "base::email"
+    }
+}
+
+impl From<Email> for String {
+    fn from(email: Email) -> Self {
+        email.0
+    }
+}
+
+#[cfg(test)]
+mod email_test {
+    use pretty_assertions::assert_eq;
+
+    use super::Email;
+    use crate::error::resource::ValidationErrorKind;
+
+    #[test]
+    fn accept_valid_emails() {
+        for email in [
+            "user@email.com",
+            "first.last@sub.domain.org",
+            "user+tag@e-mail.io",
+            "o'brien_01@email.com",
+        ] {
+            let parsed: Email = email.parse().expect("Expect a valid email");
+            assert_eq!(parsed.as_str(), email);
+        }
+    }
+
+    #[test]
+    fn reject_missing_at_sign() {
+        for email in ["user.email.com", "", "user@"] {
+            let err = email
+                .parse::<Email>()
+                .expect_err("Expect a email without domain to fail");
+            assert_eq!(err.path, "/email");
+            assert_eq!(
+                err.kinds,
+                vec![ValidationErrorKind::Pattern(Email::PATTERN.into())]
+            );
+        }
+    }
+
+    #[test]
+    fn reject_empty_local_part() {
+        for email in ["@email.com", ".user@email.com", "user.@email.com"] {
+            let err = email
+                .parse::<Email>()
+                .expect_err("Expect a email without local part to fail");
+            assert_eq!(err.path, "/email");
+        }
+    }
+
+    #[test]
+    fn reject_malformed_domain() {
+        for email in [
+            "user@email",
+            "user@-email.com",
+            "user@email..com",
+            "us er@email.com",
+        ] {
+            assert!(email.parse::<Email>().is_err(), "{email}");
+        }
+    }
+
+    #[test]
+    fn normalize_to_lowercase() {
+        let email: Email = "User.Name@EMail.COM".parse().expect("Expect a valid email");
+        assert_eq!(email.as_str(), "user.name@email.com");
+    }
+}
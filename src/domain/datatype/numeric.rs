@@ -0,0 +1,258 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::JsonPointer;
+use crate::base::ResourceID;
+use crate::error::resource::{ValidationErrorKind, ValidationFieldError};
+
+/// Exact decimal number
+///
+/// Integer mantissa scaled by a power of ten, so fractional steps as `0.5` and
+/// integers beyond the `f64` precision are represented and serialized losslessly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    mantissa: i128,
+    /// Fractional digits, without trailing zeros
+    scale: u32,
+}
+
+impl Decimal {
+    /// Maximum fractional digits.
+    pub const MAX_SCALE: u32 = 18;
+
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        let mut decimal = Self { mantissa, scale };
+        while decimal.scale > 0 && decimal.mantissa % 10 == 0 {
+            decimal.mantissa /= 10;
+            decimal.scale -= 1;
+        }
+        decimal
+    }
+
+    /// Mantissa rescaled to `scale` fractional digits.
+    fn rescaled(&self, scale: u32) -> Option<i128> {
+        10i128
+            .checked_pow(scale - self.scale)
+            .and_then(|factor| self.mantissa.checked_mul(factor))
+    }
+
+    /// Whether the value is a integer multiple of `step`.
+    ///
+    /// A zero step has no multiples.
+    pub fn is_multiple_of(&self, step: &Decimal) -> bool {
+        let scale = self.scale.max(step.scale);
+        match (self.rescaled(scale), step.rescaled(scale)) {
+            (Some(value), Some(step)) => match value.checked_rem(step) {
+                Some(rem) => rem == 0,
+                // only overflows on `i128::MIN % -1`, and every value is a multiple of -1
+                None => step == -1,
+            },
+            _ => false,
+        }
+    }
+
+    /// Validate the value to be a multiple of `step`.
+    pub fn validate_multiple_of(&self, step: &Decimal) -> Result<(), ValidationErrorKind> {
+        if self.is_multiple_of(step) {
+            return Ok(());
+        }
+        Err(ValidationErrorKind::MultipleOf(*step))
+    }
+}
+
+impl ResourceID for Decimal {
+    fn resource_id() -> &'static str {
+        "base::decimal"
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ValidationFieldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            ValidationFieldError::from_resource::<Self>(
+                s.into(),
+                JsonPointer::root(),
+                vec![ValidationErrorKind::InvalidType],
+            )
+        };
+
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let all_digits = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+        if integer.is_empty() || !all_digits(integer) || !all_digits(fraction) {
+            return Err(invalid());
+        }
+        if digits.contains('.') && fraction.is_empty() {
+            return Err(invalid());
+        }
+
+        let scale = u32::try_from(fraction.len()).map_err(|_| invalid())?;
+        if scale > Self::MAX_SCALE {
+            return Err(invalid());
+        }
+
+        let mut mantissa: i128 = 0;
+        for ch in integer.chars().chain(fraction.chars()) {
+            let digit = i128::from(ch as u8 - b'0');
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|mantissa| mantissa.checked_add(digit))
+                .ok_or_else(invalid)?;
+        }
+        if negative {
+            mantissa = -mantissa;
+        }
+
+        Ok(Self::new(mantissa, scale))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.scale == 0 {
+            return write!(f, "{}", self.mantissa);
+        }
+
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = format!(
+            "{:0>width$}",
+            self.mantissa.unsigned_abs(),
+            width = self.scale as usize + 1
+        );
+        let (integer, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        write!(f, "{sign}{integer}.{fraction}")
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, Unexpected};
+
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s)
+            .map_err(|_| Error::invalid_value(Unexpected::Str(&s), &Self::resource_id()))
+    }
+}
+
+#[cfg(test)]
+mod decimal_test {
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+
+    use super::Decimal;
+    use crate::error::resource::ValidationErrorKind;
+
+    fn decimal(s: &str) -> Decimal {
+        Decimal::from_str(s).expect("Expect a valid decimal")
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let values = [
+            ("0", "0"),
+            ("0.5", "0.5"),
+            ("-0.25", "-0.25"),
+            ("1.50", "1.5"),
+            ("10.000", "10"),
+            ("0.000001", "0.000001"),
+            ("9007199254740993", "9007199254740993"),
+        ];
+
+        for (input, expected) in values {
+            assert_eq!(decimal(input).to_string(), expected);
+        }
+
+        assert_eq!(decimal("1.50"), decimal("1.5"));
+    }
+
+    #[test]
+    fn reject_invalid_decimal() {
+        for value in [
+            "",
+            "-",
+            ".5",
+            "1.",
+            "1.2.3",
+            "1e3",
+            "0x10",
+            " 1",
+            "0.1234567890123456789",
+        ] {
+            let err = Decimal::from_str(value).expect_err("Expect a invalid decimal");
+            assert_eq!(err.kinds, vec![ValidationErrorKind::InvalidType]);
+        }
+    }
+
+    #[test]
+    fn validate_fractional_multiples() {
+        let step = decimal("0.5");
+
+        for value in ["0", "0.5", "1", "1.5", "-2.5", "100"] {
+            assert_eq!(decimal(value).validate_multiple_of(&step), Ok(()));
+        }
+
+        for value in ["0.2", "1.25", "-0.1"] {
+            assert_eq!(
+                decimal(value).validate_multiple_of(&step),
+                Err(ValidationErrorKind::MultipleOf(step))
+            );
+        }
+    }
+
+    #[test]
+    fn validate_large_integer_multiples() {
+        // beyond the f64 integer precision, 2^53 + 1
+        let value = decimal("9007199254740993");
+
+        assert!(value.is_multiple_of(&decimal("3")));
+        assert!(!value.is_multiple_of(&decimal("2")));
+        assert!(decimal("170141183460469231731687303715884105727")
+            .is_multiple_of(&decimal("170141183460469231731687303715884105727")));
+    }
+
+    #[test]
+    fn zero_step_has_no_multiples() {
+        assert!(!decimal("10").is_multiple_of(&decimal("0")));
+    }
+
+    #[test]
+    fn not_overflow_the_remainder() {
+        let min = Decimal::new(i128::MIN, 0);
+
+        assert!(min.is_multiple_of(&Decimal::new(-1, 0)));
+        assert!(min.is_multiple_of(&Decimal::new(2, 0)));
+        assert!(!min.is_multiple_of(&Decimal::new(3, 0)));
+    }
+
+    #[test]
+    fn serialize_losslessly() {
+        let kind = ValidationErrorKind::MultipleOf(decimal("0.5"));
+        let json = serde_json::to_string(&kind).expect("Expect to serialize the kind");
+        assert_eq!(json, r#"{"MultipleOf":"0.5"}"#);
+
+        let deserialized: ValidationErrorKind =
+            serde_json::from_str(&json).expect("Expect to deserialize the kind");
+        assert_eq!(deserialized, kind);
+    }
+}
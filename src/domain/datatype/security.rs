@@ -6,6 +6,7 @@ use derive_more::Display;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::JsonPointer;
 use crate::base::ResourceID;
 use crate::error::resource::{ValidationErrorKind, ValidationFieldError};
 
@@ -49,7 +50,7 @@ impl FromStr for PasswordHashAlgorithm {
             "argon2id" => Ok(Self::Argon2id),
             _ => Err(ValidationFieldError::from_resource::<Self>(
                 s.into(),
-                String::new(),
+                JsonPointer::root(),
                 vec![ValidationErrorKind::UnknownVariant],
             )),
         }
@@ -122,7 +123,7 @@ impl TryFrom<u32> for Argon2Version {
             19 => Ok(Self::V19),
             _ => Err(Self::Error::from_resource::<Self>(
                 value.to_string(),
-                String::new(),
+                JsonPointer::root(),
                 vec![ValidationErrorKind::UnknownVariant],
             )),
         }
@@ -148,12 +149,133 @@ pub struct Argon2Params {
     parallelism: u32,
 }
 
+impl Argon2Params {
+    pub const MIN_MEMORY_COST: u32 = 1;
+    pub const MIN_ITERATION_COST: u32 = 1;
+    pub const MIN_PARALLELISM: u32 = 1;
+    pub const MAX_PARALLELISM: u32 = 255;
+
+    pub fn new(
+        memory_cost: u32,
+        iteration_cost: u32,
+        parallelism: u32,
+    ) -> Result<Self, ValidationFieldError> {
+        let out_of_range = |value: u32, field: &str, kind: ValidationErrorKind| {
+            ValidationFieldError::from_resource::<Self>(
+                value.to_string(),
+                JsonPointer::new([field]),
+                vec![kind],
+            )
+        };
+
+        if memory_cost < Self::MIN_MEMORY_COST {
+            return Err(out_of_range(
+                memory_cost,
+                "memory_cost",
+                ValidationErrorKind::Minimum(Self::MIN_MEMORY_COST.into()),
+            ));
+        }
+        if iteration_cost < Self::MIN_ITERATION_COST {
+            return Err(out_of_range(
+                iteration_cost,
+                "iteration_cost",
+                ValidationErrorKind::Minimum(Self::MIN_ITERATION_COST.into()),
+            ));
+        }
+        if parallelism < Self::MIN_PARALLELISM {
+            return Err(out_of_range(
+                parallelism,
+                "parallelism",
+                ValidationErrorKind::Minimum(Self::MIN_PARALLELISM.into()),
+            ));
+        }
+        if parallelism > Self::MAX_PARALLELISM {
+            return Err(out_of_range(
+                parallelism,
+                "parallelism",
+                ValidationErrorKind::Maximum(Self::MAX_PARALLELISM.into()),
+            ));
+        }
+
+        Ok(Self {
+            memory_cost,
+            iteration_cost,
+            parallelism,
+        })
+    }
+
+    pub fn memory_cost(&self) -> u32 {
+        self.memory_cost
+    }
+
+    pub fn iteration_cost(&self) -> u32 {
+        self.iteration_cost
+    }
+
+    pub fn parallelism(&self) -> u32 {
+        self.parallelism
+    }
+}
+
 impl ResourceID for Argon2Params {
     fn resource_id() -> &'static str {
         "base::argon2_parameter"
     }
 }
 
+#[cfg(test)]
+mod argon2_params_test {
+    use pretty_assertions::assert_eq;
+
+    use super::Argon2Params;
+    use crate::error::resource::ValidationErrorKind;
+
+    #[test]
+    fn create_valid_params() {
+        let params_list = [
+            (1, 1, 1),
+            (16, 3, 1),
+            (19 * 1024, 2, 255),
+            (u32::MAX, u32::MAX, 4),
+        ];
+
+        for (memory_cost, iteration_cost, parallelism) in params_list {
+            let params = Argon2Params::new(memory_cost, iteration_cost, parallelism)
+                .expect("Expect valid argon2 params");
+
+            assert_eq!(params.memory_cost(), memory_cost);
+            assert_eq!(params.iteration_cost(), iteration_cost);
+            assert_eq!(params.parallelism(), parallelism);
+        }
+    }
+
+    #[test]
+    fn reject_out_of_range_params() {
+        let params_list = [
+            ((0, 1, 1), "/memory_cost", ValidationErrorKind::Minimum(1)),
+            (
+                (1, 0, 1),
+                "/iteration_cost",
+                ValidationErrorKind::Minimum(1),
+            ),
+            ((1, 1, 0), "/parallelism", ValidationErrorKind::Minimum(1)),
+            (
+                (1, 1, 256),
+                "/parallelism",
+                ValidationErrorKind::Maximum(255),
+            ),
+        ];
+
+        for ((memory_cost, iteration_cost, parallelism), path, kind) in params_list {
+            let err = Argon2Params::new(memory_cost, iteration_cost, parallelism)
+                .expect_err("Expect out of range argon2 params");
+
+            assert_eq!(err.path, path);
+            assert_eq!(err.kinds, vec![kind]);
+        }
+    }
+}
+
 impl From<Argon2Params> for password_hash::ParamsString {
     fn from(params: Argon2Params) -> Self {
         let mut output = password_hash::ParamsString::new();
@@ -319,7 +441,7 @@ impl FromStr for PasswordHash {
             password_hash::PasswordHash::parse(s, password_hash::Encoding::B64).map_err(|_| {
                 Self::Err::from_resource::<Self>(
                     String::new(),
-                    String::new(),
+                    JsonPointer::root(),
                     vec![ValidationErrorKind::Invalid],
                 )
             })?;
@@ -439,6 +561,76 @@ mod password_hash_test {
     }
 }
 
+#[cfg(test)]
+mod password_hash_property_test {
+    use std::str::FromStr;
+
+    use proptest::{collection::vec, option, prelude::*};
+
+    use super::{Argon2Params, Argon2Version, OutputHash, PasswordHash, SaltString};
+
+    fn salt_and_hash() -> impl Strategy<Value = (Option<SaltString>, Option<OutputHash>)> {
+        option::of((
+            vec(any::<u8>(), 8..=48),
+            option::of(vec(
+                any::<u8>(),
+                OutputHash::MIN_LENGTH..=OutputHash::MAX_LENGTH,
+            )),
+        ))
+        .prop_map(|salt_and_hash| match salt_and_hash {
+            Some((salt, hash)) => (
+                Some(SaltString::b64_encode(&salt).expect("Expect a valid salt length")),
+                hash.map(|hash| OutputHash::new(&hash).expect("Expect a valid output length")),
+            ),
+            None => (None, None),
+        })
+    }
+
+    fn argon2_hash() -> impl Strategy<Value = PasswordHash> {
+        (
+            prop_oneof![
+                Just(argon2::Algorithm::Argon2d),
+                Just(argon2::Algorithm::Argon2i),
+                Just(argon2::Algorithm::Argon2id),
+            ],
+            prop_oneof![Just(Argon2Version::V16), Just(Argon2Version::V19)],
+            1..=u32::MAX,
+            1..=u32::MAX,
+            1..=255_u32,
+            salt_and_hash(),
+        )
+            .prop_map(
+                |(algorithm, version, memory_cost, iteration_cost, parallelism, (salt, hash))| {
+                    PasswordHash::new_argon2(
+                        algorithm,
+                        version,
+                        Argon2Params::new(memory_cost, iteration_cost, parallelism)
+                            .expect("Expect valid argon2 params"),
+                        salt,
+                        hash,
+                    )
+                },
+            )
+    }
+
+    fn bcrypt_hash() -> impl Strategy<Value = PasswordHash> {
+        (4..=31_u32, salt_and_hash())
+            .prop_map(|(cost, (salt, hash))| PasswordHash::new_bcrypt(cost, salt, hash))
+    }
+
+    proptest! {
+        #[test]
+        fn serialize_and_parse(hash in prop_oneof![argon2_hash(), bcrypt_hash()]) {
+            let encoded = hash.to_string();
+            let parsed = PasswordHash::from_str(&encoded)
+                .expect("Expect to parse a serialized password hash");
+
+            prop_assert_eq!(&parsed, &hash);
+            prop_assert_eq!(parsed.to_string(), encoded, "Expect a stable display");
+        }
+    }
+}
+
 #[derive(Debug, Display, PartialEq, Eq)]
 pub enum PasswordHashError {
     /// Unsupported Algorithm.
@@ -447,6 +639,10 @@ pub enum PasswordHashError {
     /// Invalid password.
     InvalidPassword,
 
+    /// Password longer than the algorithm hashes, in bytes.
+    #[display(fmt = "PasswordTooLong({})", _0)]
+    PasswordTooLong(usize),
+
     /// Invalid password hash.
     InvalidPasswordHash,
 
@@ -507,12 +703,22 @@ impl From<argon2::Error> for PasswordHashError {
 }
 
 /// Token issuer
-#[derive(Debug, PartialEq, Eq)]
-pub struct TokenIssuer;
+///
+/// Configured per environment, so tokens issued by a staging deployment are rejected in production.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenIssuer(String);
 
 impl TokenIssuer {
-    pub fn as_str() -> &'static str {
-        "conduit.blog.app"
+    pub const DEFAULT: &'static str = "conduit.blog.app";
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for TokenIssuer {
+    fn default() -> Self {
+        Self(Self::DEFAULT.into())
     }
 }
 
@@ -524,21 +730,25 @@ impl ResourceID for TokenIssuer {
 
 impl fmt::Display for TokenIssuer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(Self::as_str())
+        f.write_str(self.as_str())
     }
 }
 
 impl FromStr for TokenIssuer {
     type Err = ValidationFieldError;
 
+    /// Parse a issuer name.
+    ///
+    /// Any non-empty name without whitespace is accepted, the token service
+    /// validates it against the configured issuer.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s == Self::as_str() {
-            return Ok(Self);
+        if !s.is_empty() && !s.contains(char::is_whitespace) {
+            return Ok(Self(s.into()));
         }
 
         Err(Self::Err::from_resource::<Self>(
             s.into(),
-            String::new(),
+            JsonPointer::root(),
             vec![ValidationErrorKind::Invalid],
         ))
     }
@@ -549,7 +759,7 @@ impl Serialize for TokenIssuer {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(Self::as_str())
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -569,7 +779,7 @@ impl<'de> Deserialize<'de> for TokenIssuer {
 /// Token subject (sub)
 ///
 /// Whom token refers to.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenSubject {
     User(Uuid),
     Public,
@@ -593,7 +803,7 @@ impl FromStr for TokenSubject {
             let id = Uuid::from_str(id_str).map_err(|_| {
                 Self::Err::from_resource::<Self>(
                     s.into(),
-                    String::new(),
+                    JsonPointer::root(),
                     vec![ValidationErrorKind::Pattern("^user:<uuid>$".into())],
                 )
             })?;
@@ -602,7 +812,7 @@ impl FromStr for TokenSubject {
 
         Err(Self::Err::from_resource::<Self>(
             s.into(),
-            String::new(),
+            JsonPointer::root(),
             vec![ValidationErrorKind::UnknownVariant],
         ))
     }
@@ -654,7 +864,7 @@ pub struct TokenPayload<T> {
 }
 
 impl<T> TokenPayload<T> {
-    pub fn new(expiration: Duration, subject: TokenSubject, data: T) -> Self {
+    pub fn new(expiration: Duration, issuer: TokenIssuer, subject: TokenSubject, data: T) -> Self {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .expect("Time went backwards")
@@ -663,7 +873,7 @@ impl<T> TokenPayload<T> {
         Self {
             exp: now + expiration.as_secs(),
             iat: now,
-            iss: TokenIssuer,
+            iss: issuer,
             sub: subject,
             data,
         }
@@ -723,6 +933,127 @@ impl<T> From<Token<T>> for String {
     }
 }
 
+/// Kind of a token, held in the payload data.
+///
+/// Access tokens hold no data, so only the refresh tokens carry a kind, and are
+/// rejected where a access token is expected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    /// Long-lived token, only exchanged for a new access token.
+    Refresh,
+}
+
+/// Character class a password may be required to contain.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CharClass {
+    #[display(fmt = "lowercase")]
+    Lowercase,
+    #[display(fmt = "uppercase")]
+    Uppercase,
+    #[display(fmt = "digit")]
+    Digit,
+    #[display(fmt = "symbol")]
+    Symbol,
+}
+
+impl CharClass {
+    pub fn contains(&self, ch: char) -> bool {
+        match self {
+            CharClass::Lowercase => ch.is_lowercase(),
+            CharClass::Uppercase => ch.is_uppercase(),
+            CharClass::Digit => ch.is_ascii_digit(),
+            CharClass::Symbol => !ch.is_alphanumeric() && !ch.is_whitespace(),
+        }
+    }
+
+    /// Pattern reported when the class is missing.
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            CharClass::Lowercase => "\\p{Ll}",
+            CharClass::Uppercase => "\\p{Lu}",
+            CharClass::Digit => "[0-9]",
+            CharClass::Symbol => "[^\\p{L}\\p{N}\\s]",
+        }
+    }
+}
+
+/// Strength requirements of a password.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    /// Minimum length in characters.
+    pub min_length: u64,
+    /// Classes with at least one character in the password.
+    pub required_classes: Vec<CharClass>,
+}
+
+impl PasswordPolicy {
+    pub const DEFAULT_MIN_LENGTH: u64 = 8;
+
+    /// Validation errors of a password not meeting the policy.
+    pub fn validate(&self, pwd: &str) -> Vec<ValidationErrorKind> {
+        let mut kinds = Vec::new();
+
+        if (pwd.chars().count() as u64) < self.min_length {
+            kinds.push(ValidationErrorKind::MinLength(self.min_length));
+        }
+
+        for class in &self.required_classes {
+            if !pwd.chars().any(|ch| class.contains(ch)) {
+                kinds.push(ValidationErrorKind::Pattern(class.pattern().into()));
+            }
+        }
+
+        kinds
+    }
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: Self::DEFAULT_MIN_LENGTH,
+            required_classes: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod password_policy_test {
+    use pretty_assertions::assert_eq;
+
+    use super::{CharClass, PasswordPolicy};
+    use crate::error::resource::ValidationErrorKind;
+
+    #[test]
+    fn accept_default_policy() {
+        assert_eq!(PasswordPolicy::default().validate("12345678"), vec![]);
+        assert_eq!(
+            PasswordPolicy::default().validate("1234567"),
+            vec![ValidationErrorKind::MinLength(8)]
+        );
+    }
+
+    #[test]
+    fn report_missing_classes() {
+        let policy = PasswordPolicy {
+            min_length: 10,
+            required_classes: vec![CharClass::Uppercase, CharClass::Digit, CharClass::Symbol],
+        };
+
+        assert_eq!(policy.validate("Secure:12345"), vec![]);
+        assert_eq!(
+            policy.validate("secure"),
+            vec![
+                ValidationErrorKind::MinLength(10),
+                ValidationErrorKind::Pattern(CharClass::Uppercase.pattern().into()),
+                ValidationErrorKind::Pattern(CharClass::Digit.pattern().into()),
+                ValidationErrorKind::Pattern(CharClass::Symbol.pattern().into()),
+            ]
+        );
+    }
+}
+
 #[derive(Debug)]
 pub enum TokenEncryptionError {
     /// A invalid token
@@ -738,6 +1069,15 @@ pub enum TokenEncryptionError {
 
     /// A token with a valid signature and a invalid payload
     InvalidPayload,
+
+    /// A valid token, with data of a different shape than expected.
+    ///
+    /// The token was issued for other purpose, e.g. a access token presented where
+    /// a email verification token is expected.
+    UnexpectedPayload,
+
+    /// A token signed with a key that is not known, or no longer retained after rotations.
+    UnknownKey,
 }
 
 impl From<jsonwebtoken::errors::Error> for TokenEncryptionError {
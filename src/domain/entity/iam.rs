@@ -1,7 +1,7 @@
 use url::Url;
 use uuid::Uuid;
 
-use crate::domain::datatype::security::PasswordHash;
+use crate::domain::datatype::{security::PasswordHash, Bio};
 
 use super::{impl_entity, state_ref, transform_helper, EntityData};
 
@@ -12,6 +12,7 @@ pub struct UserState {
     pub(in crate::domain) password_hash: PasswordHash,
     pub(in crate::domain) bio: Option<String>,
     pub(in crate::domain) image_url: Option<Url>,
+    pub(in crate::domain) deactivated: bool,
 }
 
 #[derive(Debug)]
@@ -28,6 +29,7 @@ impl User {
     state_ref!(password_hash, PasswordHash);
     state_ref!(bio, Option<String>);
     state_ref!(image_url, Option<Url>);
+    state_ref!(deactivated, bool);
 
     transform_helper!(UserState);
 
@@ -40,14 +42,36 @@ impl User {
                 password_hash,
                 bio: None,
                 image_url: None,
+                deactivated: false,
             },
         )
     }
 
-    pub fn update(&mut self, bio: Option<String>, image_url: Option<Url>) {
-        self.state.bio = bio;
+    pub fn update(&mut self, bio: Option<Bio>, image_url: Option<Url>) {
+        self.state.bio = bio.map(String::from);
         self.state.image_url = image_url;
 
         self.data.update();
     }
+
+    /// Replace the password hash, computed from the same password with other params.
+    pub fn rehash_password(&mut self, password_hash: PasswordHash) {
+        self.state.password_hash = password_hash;
+        self.data.update();
+    }
+
+    /// Deactivate the account, retaining all the user data.
+    pub fn deactivate(&mut self) {
+        if !self.state.deactivated {
+            self.state.deactivated = true;
+            self.data.update();
+        }
+    }
+
+    pub fn reactivate(&mut self) {
+        if self.state.deactivated {
+            self.state.deactivated = false;
+            self.data.update();
+        }
+    }
 }
@@ -1,6 +1,8 @@
 pub mod iam;
+pub mod profile;
 
 use chrono::{DateTime, Utc};
+use derive_more::Display;
 use uuid::Uuid;
 
 pub trait Entity {
@@ -10,6 +12,25 @@ pub trait Entity {
     fn updated(&self) -> Option<DateTime<Utc>>;
 }
 
+/// Opaque tag of a entity version, used as the resource ETag.
+///
+/// Encodes the version in base 36, so clients don't rely on the numeric version.
+pub fn version_tag(version: u32) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    let mut version = version;
+    let mut tag = Vec::new();
+    loop {
+        tag.push(char::from(DIGITS[(version % 36) as usize]));
+        version /= 36;
+        if version == 0 {
+            break;
+        }
+    }
+
+    tag.iter().rev().collect()
+}
+
 /// Data used to restore a entity
 #[derive(Debug, Clone)]
 pub struct EntityData {
@@ -20,15 +41,46 @@ pub struct EntityData {
 }
 
 impl EntityData {
+    /// Version of a newly created entity.
+    pub const INITIAL_VERSION: u32 = 1;
+
     pub fn new(id: Uuid) -> Self {
         Self {
             id,
             created: Utc::now(),
             updated: None,
-            version: 1,
+            version: Self::INITIAL_VERSION,
         }
     }
 
+    /// Restore the entity data from a persisted state.
+    ///
+    /// Reject data that a entity could never reach, as a version lower than the initial
+    /// version or a update time before the creation time.
+    pub fn restore(
+        id: Uuid,
+        created: DateTime<Utc>,
+        updated: Option<DateTime<Utc>>,
+        version: u32,
+    ) -> Result<Self, EntityDataError> {
+        if version < Self::INITIAL_VERSION {
+            return Err(EntityDataError::InvalidVersion(version));
+        }
+
+        if let Some(updated) = updated {
+            if updated < created {
+                return Err(EntityDataError::UpdatedBeforeCreated { created, updated });
+            }
+        }
+
+        Ok(Self {
+            id,
+            created,
+            updated,
+            version,
+        })
+    }
+
     /// Update the entity data.
     ///
     /// Icrement the entity version by 1 and set the updated time as now.
@@ -38,6 +90,80 @@ impl EntityData {
     }
 }
 
+/// Inconsistent entity data.
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
+pub enum EntityDataError {
+    /// Version lower than the initial version.
+    #[display(fmt = "invalid entity version {_0}")]
+    InvalidVersion(u32),
+
+    /// Update time before the creation time.
+    #[display(fmt = "entity updated at {updated} before created at {created}")]
+    UpdatedBeforeCreated {
+        created: DateTime<Utc>,
+        updated: DateTime<Utc>,
+    },
+}
+
+impl std::error::Error for EntityDataError {}
+
+#[cfg(test)]
+mod entity_data_test {
+    use chrono::{Duration, Utc};
+    use pretty_assertions::{assert_eq, assert_ne};
+    use uuid::Uuid;
+
+    use super::{version_tag, EntityData, EntityDataError};
+
+    #[test]
+    fn restore_valid_data() {
+        let created = Utc::now();
+        let updated = created + Duration::seconds(1);
+
+        let data = EntityData::restore(Uuid::new_v4(), created, None, 1)
+            .expect("Expect to restore a never updated entity");
+        assert_eq!(data.version, EntityData::INITIAL_VERSION);
+
+        let data = EntityData::restore(Uuid::new_v4(), created, Some(updated), 2)
+            .expect("Expect to restore a updated entity");
+        assert_eq!(data.updated, Some(updated));
+
+        EntityData::restore(Uuid::new_v4(), created, Some(created), 2)
+            .expect("Expect to restore a entity updated at the creation time");
+    }
+
+    #[test]
+    fn encode_version_tag() {
+        assert_eq!(version_tag(1), "1");
+        assert_eq!(version_tag(35), "z");
+        assert_eq!(version_tag(36), "10");
+        assert_eq!(version_tag(u32::MAX), "1z141z3");
+        assert_ne!(version_tag(1), version_tag(2));
+    }
+
+    #[test]
+    fn reject_version_zero() {
+        let err = EntityData::restore(Uuid::new_v4(), Utc::now(), None, 0)
+            .expect_err("Expect to reject a entity with version 0");
+
+        assert_eq!(err, EntityDataError::InvalidVersion(0));
+    }
+
+    #[test]
+    fn reject_updated_before_created() {
+        let created = Utc::now();
+        let updated = created - Duration::seconds(1);
+
+        let err = EntityData::restore(Uuid::new_v4(), created, Some(updated), 2)
+            .expect_err("Expect to reject a entity updated before created");
+
+        assert_eq!(
+            err,
+            EntityDataError::UpdatedBeforeCreated { created, updated }
+        );
+    }
+}
+
 macro_rules! transform_helper {
     ($state_ty:ty) => {
         pub(in crate::domain) fn restore(
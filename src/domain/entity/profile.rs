@@ -0,0 +1,33 @@
+use uuid::Uuid;
+
+use super::{impl_entity, state_ref, EntityData};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FollowState {
+    pub(in crate::domain) follower_id: Uuid,
+    pub(in crate::domain) following_id: Uuid,
+}
+
+/// A user following the profile of other user.
+#[derive(Debug)]
+pub struct Follow {
+    pub(in crate::domain) data: EntityData,
+    pub(in crate::domain) state: FollowState,
+}
+
+impl_entity!(Follow);
+
+impl Follow {
+    state_ref!(follower_id, Uuid);
+    state_ref!(following_id, Uuid);
+
+    pub fn new(id: Uuid, follower_id: Uuid, following_id: Uuid) -> Self {
+        Self {
+            data: EntityData::new(id),
+            state: FollowState {
+                follower_id,
+                following_id,
+            },
+        }
+    }
+}
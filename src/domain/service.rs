@@ -1,13 +1,41 @@
-use super::datatype::security::{
-    PasswordHash, PasswordHashError, Token, TokenEncryptionError, TokenPayload,
+use async_trait::async_trait;
+
+use super::datatype::{
+    audit::AuditEvent,
+    security::{
+        PasswordHash, PasswordHashAlgorithm, PasswordHashError, PasswordParams, Token,
+        TokenEncryptionError, TokenIssuer, TokenPayload,
+    },
 };
 
 pub trait PasswordHashService {
     fn hash_password(&self, pwd: &str) -> Result<PasswordHash, PasswordHashError>;
     fn verify_password(&self, pwd: &str, hash: &PasswordHash) -> Result<(), PasswordHashError>;
+
+    /// Algorithm of the new hashes.
+    fn algorithm(&self) -> PasswordHashAlgorithm;
+
+    /// Params of the new hashes.
+    fn hash_params(&self) -> PasswordParams;
+
+    /// Hash computed by other algorithm or params than the configured ones.
+    fn needs_rehash(&self, hash: &PasswordHash) -> bool {
+        *hash.algorithm() != self.algorithm() || *hash.params() != self.hash_params()
+    }
+
+    /// Spend the time of a password verification, for a credential of no account.
+    ///
+    /// A unknown account is not told apart from a wrong password by the response time.
+    fn verify_dummy_password(&self, pwd: &str) {
+        // hashing with the configured params costs the same as verifying a new hash
+        let _ = self.hash_password(pwd);
+    }
 }
 
 pub trait TokenEncryptionService {
+    /// Issuer of the tokens, the only one accepted on verification.
+    fn issuer(&self) -> &TokenIssuer;
+
     fn issue_token<T>(&self, payload: &TokenPayload<T>) -> Result<String, TokenEncryptionError>
     where
         T: serde::Serialize;
@@ -15,6 +43,22 @@ pub trait TokenEncryptionService {
     fn verify_token<T>(&self, token: &str) -> Result<TokenPayload<T>, TokenEncryptionError>
     where
         T: serde::de::DeserializeOwned;
+
+    /// Verify the token as [`Self::verify_token`], except its expiration.
+    ///
+    /// Only to inspect the claims, a expired token still does not authenticate.
+    fn inspect_token<T>(&self, token: &str) -> Result<TokenPayload<T>, TokenEncryptionError>
+    where
+        T: serde::de::DeserializeOwned;
+}
+
+/// Audit trail of security relevant actions.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Record a audit event.
+    ///
+    /// Failing to record must not fail the audited action.
+    async fn record(&self, event: AuditEvent);
 }
 
 impl<T> Token<T> {
@@ -4,12 +4,16 @@ use super::entity::EntityData;
 
 impl<'r> FromRow<'r, PgRow> for EntityData {
     fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
-        Ok(Self {
-            id: row.try_get("id")?,
-            version: row.try_get::<i64, &str>("version")? as u32,
-            created: row.try_get("created")?,
-            updated: row.try_get("updated")?,
-        })
+        let version = u32::try_from(row.try_get::<i64, &str>("version")?)
+            .map_err(|err| sqlx::Error::Decode(err.into()))?;
+
+        Self::restore(
+            row.try_get("id")?,
+            row.try_get("created")?,
+            row.try_get("updated")?,
+            version,
+        )
+        .map_err(|err| sqlx::Error::Decode(err.into()))
     }
 }
 
@@ -37,7 +41,7 @@ macro_rules! column_decode_error {
 mod iam {
     use sqlx::{postgres::PgRow, FromRow, Row};
 
-    use crate::app::resource::iam::UserResponse;
+    use crate::app::resource::{iam::UserResponse, profile::ProfileResponse};
     use crate::domain::entity::{
         iam::{User, UserState},
         EntityData,
@@ -65,6 +69,7 @@ mod iam {
                     ),
                 ),
                 username: row.try_get("username")?,
+                deactivated: row.try_get("deactivated")?,
             })
         }
     }
@@ -93,4 +98,21 @@ mod iam {
             }
         }
     }
+
+    impl From<User> for ProfileResponse {
+        fn from(user: User) -> Self {
+            let (ent, state) = user.unmount_state();
+            Self {
+                id: ent.id,
+                created: ent.created,
+                updated: ent.updated,
+                version: ent.version,
+                username: state.username,
+                bio: state.bio,
+                image_url: state.image_url,
+                // without a requester, the profile is not followed
+                following: false,
+            }
+        }
+    }
 }
@@ -66,6 +66,7 @@ pub mod app {
 
     use super::{
         http::ErrorResponse,
+        operation::OperationError,
         persistence::{MutationError, PersistenceError},
         resource::{ConflictError, NotFoundError, ValidationError},
         security::{AuthenticationError, ForbiddenError},
@@ -79,7 +80,7 @@ pub mod app {
         Conflict(ConflictError),
         NotFound(NotFoundError),
         // Domain errors
-        // Operation(OperationError) -> 422 Unprocessable Entity
+        Operation(OperationError),
         Persistence(PersistenceError),
     }
 
@@ -115,6 +116,12 @@ pub mod app {
         }
     }
 
+    impl<R> From<OperationError> for ApplicationError<R> {
+        fn from(err: OperationError) -> Self {
+            Self::Operation(err)
+        }
+    }
+
     impl<R> From<PersistenceError> for ApplicationError<R> {
         fn from(err: PersistenceError) -> Self {
             Self::Persistence(err)
@@ -130,6 +137,18 @@ pub mod app {
         }
     }
 
+    impl<R> ApplicationError<R> {
+        /// Nest the validation field paths under `prefix`.
+        pub fn nested(self, prefix: &str) -> Self {
+            match self {
+                ApplicationError::Validation(err) => {
+                    ApplicationError::Validation(err.nested(prefix))
+                }
+                err => err,
+            }
+        }
+    }
+
     impl<R: Serialize + Send> Piece for ApplicationError<R> {
         fn render(self, res: &mut salvo::Response) {
             let status = match &self {
@@ -139,6 +158,7 @@ pub mod app {
                 ApplicationError::Forbidden(_) => StatusError::forbidden(),
                 ApplicationError::Conflict(_) => StatusError::conflict(),
                 ApplicationError::NotFound(_) => StatusError::not_found(),
+                ApplicationError::Operation(_) => StatusError::unprocessable_entity(),
             };
             res.render(Json(ErrorResponse::from_status_error(&status, self)));
             res.set_status_error(status);
@@ -178,10 +198,22 @@ pub mod persistence {
 
     pub type SqlState = String;
 
+    /// Database error context for the server logs.
+    ///
+    /// Never serialized, as it may reveal the schema to clients.
+    #[derive(Debug, Display, Clone, Default, PartialEq, Eq)]
+    #[display(fmt = "constraint {constraint:?}: {message}")]
+    pub struct DatabaseErrorDetail {
+        /// Name of the violated constraint.
+        pub constraint: Option<String>,
+        /// Message reported by the database.
+        pub message: String,
+    }
+
     #[derive(Debug, Display)]
     pub enum PersistenceError {
-        #[display(fmt = "database persistence error: SQLSTATE {_0:?}")]
-        Database(Option<SqlState>),
+        #[display(fmt = "database persistence error: SQLSTATE {_0:?}, {_1}")]
+        Database(Option<SqlState>, DatabaseErrorDetail),
         #[display(fmt = "persistence layer connection error: {_0}")]
         Connection(DispatchError),
         #[display(fmt = "PersistenceError data not found")]
@@ -214,7 +246,19 @@ pub mod persistence {
                 SqlxError::Configuration(_) => {
                     Self::Connection(DispatchError::IO(io::ErrorKind::InvalidInput.into()))
                 }
-                SqlxError::Database(db) => Self::Database(db.code().map(|code| code.into())),
+                SqlxError::Database(db) => {
+                    let detail = DatabaseErrorDetail {
+                        constraint: db.constraint().map(String::from),
+                        message: db.message().into(),
+                    };
+                    tracing::error!(
+                        target = "database",
+                        code = ?db.code(),
+                        constraint = ?detail.constraint,
+                        message = %detail.message,
+                    );
+                    Self::Database(db.code().map(|code| code.into()), detail)
+                }
                 SqlxError::Io(io) => Self::Connection(DispatchError::IO(io)),
                 SqlxError::Tls(_) => {
                     Self::Connection(DispatchError::IO(io::ErrorKind::ConnectionRefused.into()))
@@ -261,6 +305,140 @@ pub mod persistence {
     }
 
     impl error::Error for MutationError {}
+
+    #[cfg(test)]
+    mod persistence_error_test {
+        use std::{
+            borrow::Cow,
+            error, fmt, io,
+            sync::{Arc, Mutex},
+        };
+
+        use pretty_assertions::assert_eq;
+        use salvo::{
+            handler,
+            test::{ResponseExt, TestClient},
+            Response, Router, Service,
+        };
+        use sqlx::error::DatabaseError;
+
+        use super::{DatabaseErrorDetail, PersistenceError};
+        use crate::error::app::ApplicationError;
+
+        #[derive(Debug)]
+        struct UniqueViolation;
+
+        impl fmt::Display for UniqueViolation {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.message())
+            }
+        }
+
+        impl error::Error for UniqueViolation {}
+
+        impl DatabaseError for UniqueViolation {
+            fn message(&self) -> &str {
+                "duplicate key value violates unique constraint \"user_unique_email\""
+            }
+
+            fn code(&self) -> Option<Cow<'_, str>> {
+                Some(Cow::Borrowed("23505"))
+            }
+
+            fn constraint(&self) -> Option<&str> {
+                Some("user_unique_email")
+            }
+
+            fn as_error(&self) -> &(dyn error::Error + Send + Sync + 'static) {
+                self
+            }
+
+            fn as_error_mut(&mut self) -> &mut (dyn error::Error + Send + Sync + 'static) {
+                self
+            }
+
+            fn into_error(self: Box<Self>) -> Box<dyn error::Error + Send + Sync + 'static> {
+                self
+            }
+        }
+
+        #[derive(Clone, Default)]
+        struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+        impl io::Write for LogBuffer {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0
+                    .lock()
+                    .expect("Expect the log buffer lock not to be poisoned")
+                    .extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        fn unique_violation() -> PersistenceError {
+            PersistenceError::from(sqlx::Error::Database(Box::new(UniqueViolation)))
+        }
+
+        #[handler]
+        async fn persistence_error_handler(res: &mut Response) {
+            res.render(ApplicationError::<()>::from(unique_violation()));
+        }
+
+        #[test]
+        fn log_database_error_detail() {
+            let buffer = LogBuffer::default();
+            let writer = buffer.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_ansi(false)
+                .with_writer(move || writer.clone())
+                .finish();
+
+            let err = tracing::subscriber::with_default(subscriber, unique_violation);
+
+            match err {
+                PersistenceError::Database(code, detail) => {
+                    assert_eq!(code.as_deref(), Some("23505"));
+                    assert_eq!(
+                        detail,
+                        DatabaseErrorDetail {
+                            constraint: Some("user_unique_email".into()),
+                            message: UniqueViolation.message().into(),
+                        }
+                    );
+                }
+                err => panic!("Expect a database error, found {err}"),
+            }
+
+            let logs = String::from_utf8(
+                buffer
+                    .0
+                    .lock()
+                    .expect("Expect the log buffer lock not to be poisoned")
+                    .clone(),
+            )
+            .expect("Expect utf8 logs");
+            assert!(logs.contains("user_unique_email"));
+        }
+
+        #[tokio::test]
+        async fn omit_detail_from_response() {
+            let service = Service::new(Router::new().get(persistence_error_handler));
+
+            let body = TestClient::get("http://localhost/")
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .expect("Expect a response body");
+
+            assert!(!body.contains("user_unique_email"));
+            assert!(!body.contains("duplicate key"));
+        }
+    }
 }
 
 pub mod resource {
@@ -269,6 +447,7 @@ pub mod resource {
     use uuid::Uuid;
 
     use crate::base::ResourceID;
+    use crate::domain::datatype::{numeric::Decimal, security::PasswordPolicy, JsonPointer};
 
     #[derive(Debug, Display, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
     pub enum ValidationErrorKind {
@@ -304,7 +483,7 @@ pub mod resource {
         /// Inclusive higher bound exceeded.
         Maximum(u64),
         /// When some number is not a multiple of another number.
-        MultipleOf(i64),
+        MultipleOf(Decimal),
         /// When some number is not positive.
         Positive,
         /// When some number is not negative.
@@ -321,6 +500,10 @@ pub mod resource {
         /// Input value already exists.
         AlreadyExists,
         /// Input value was not found.
+        ///
+        /// A value referenced in the request body that doesn't exist, e.g. the
+        /// email of a credential. A missing resource addressed by the request
+        /// path is a [`NotFoundError`] instead.
         NotFound,
         /// Generic kind.
         Invalid,
@@ -328,6 +511,85 @@ pub mod resource {
 
     impl std::error::Error for ValidationErrorKind {}
 
+    #[cfg(test)]
+    mod validation_error_kind_test {
+        use pretty_assertions::assert_eq;
+
+        use super::ValidationErrorKind;
+        use crate::domain::datatype::numeric::Decimal;
+
+        /// One value of every kind.
+        ///
+        /// The exhaustive match fails to compile when a kind is added, so it is added here too.
+        fn every_kind() -> Vec<ValidationErrorKind> {
+            let kinds = vec![
+                ValidationErrorKind::AdditionalProperties(vec!["extra".into()]),
+                ValidationErrorKind::MinProperties(1),
+                ValidationErrorKind::MaxProperties(10),
+                ValidationErrorKind::PropertyName("name".into()),
+                ValidationErrorKind::Required,
+                ValidationErrorKind::MaxLength(255),
+                ValidationErrorKind::MinLength(8),
+                ValidationErrorKind::Pattern("^[a-z]+$".into()),
+                ValidationErrorKind::NegativePattern("<script".into()),
+                ValidationErrorKind::MinItems(1),
+                ValidationErrorKind::MaxItems(20),
+                ValidationErrorKind::Minimum(0),
+                ValidationErrorKind::Maximum(100),
+                ValidationErrorKind::MultipleOf(Decimal::new(5, 1)),
+                ValidationErrorKind::Positive,
+                ValidationErrorKind::Negative,
+                ValidationErrorKind::UnknownVariant,
+                ValidationErrorKind::InvalidType,
+                ValidationErrorKind::Unverified,
+                ValidationErrorKind::Duplicated,
+                ValidationErrorKind::AlreadyExists,
+                ValidationErrorKind::NotFound,
+                ValidationErrorKind::Invalid,
+            ];
+
+            for kind in &kinds {
+                match kind {
+                    ValidationErrorKind::AdditionalProperties(_)
+                    | ValidationErrorKind::MinProperties(_)
+                    | ValidationErrorKind::MaxProperties(_)
+                    | ValidationErrorKind::PropertyName(_)
+                    | ValidationErrorKind::Required
+                    | ValidationErrorKind::MaxLength(_)
+                    | ValidationErrorKind::MinLength(_)
+                    | ValidationErrorKind::Pattern(_)
+                    | ValidationErrorKind::NegativePattern(_)
+                    | ValidationErrorKind::MinItems(_)
+                    | ValidationErrorKind::MaxItems(_)
+                    | ValidationErrorKind::Minimum(_)
+                    | ValidationErrorKind::Maximum(_)
+                    | ValidationErrorKind::MultipleOf(_)
+                    | ValidationErrorKind::Positive
+                    | ValidationErrorKind::Negative
+                    | ValidationErrorKind::UnknownVariant
+                    | ValidationErrorKind::InvalidType
+                    | ValidationErrorKind::Unverified
+                    | ValidationErrorKind::Duplicated
+                    | ValidationErrorKind::AlreadyExists
+                    | ValidationErrorKind::NotFound
+                    | ValidationErrorKind::Invalid => {}
+                }
+            }
+
+            kinds
+        }
+
+        #[test]
+        fn serde_round_trip_every_kind() {
+            for kind in every_kind() {
+                let json = serde_json::to_string(&kind).expect("Expect to serialize the kind");
+                let deserialized: ValidationErrorKind =
+                    serde_json::from_str(&json).expect("Expect to deserialize the kind");
+                assert_eq!(deserialized, kind);
+            }
+        }
+    }
+
     #[derive(Debug, Error, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
     pub struct ValidationError<R> {
         /// Resource value
@@ -349,6 +611,20 @@ pub mod resource {
                 fields,
             }
         }
+
+        /// Nest the field paths under `prefix`, when the resource is enveloped in the body.
+        ///
+        /// # Panic
+        ///
+        /// Panics if `prefix` is not a valid json pointer
+        pub fn nested(mut self, prefix: &str) -> Self {
+            let prefix: JsonPointer = prefix.parse().expect("Expect a valid json pointer prefix");
+            for field in &mut self.fields {
+                field.path =
+                    std::mem::replace(&mut field.path, JsonPointer::root()).nested(&prefix);
+            }
+            self
+        }
     }
 
     impl<R> std::fmt::Display for ValidationError<R> {
@@ -364,19 +640,33 @@ pub mod resource {
     #[display(fmt = "{path}: {value:?}, {kinds:?}")]
     pub struct ValidationFieldError {
         /// Resource field path with invalid value
-        pub path: String,
+        pub path: JsonPointer,
         /// Displayed invalid value
         pub value: String,
         /// Value type id
         pub type_id: &'static str,
         /// Kinds of validation errors
         pub kinds: Vec<ValidationErrorKind>,
+        /// Machine readable details of the field requirements
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub extension: Option<FieldExtension>,
+    }
+
+    /// Requirements of a field, for the client to render guidance.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum FieldExtension {
+        PasswordPolicy(PasswordPolicy),
+        /// Offset past which the list has to be paged with a cursor instead.
+        CursorPagination {
+            max_offset: u32,
+        },
     }
 
     impl ValidationFieldError {
         pub fn from_resource<T>(
             value: String,
-            path: String,
+            path: JsonPointer,
             kinds: Vec<ValidationErrorKind>,
         ) -> Self
         where
@@ -387,13 +677,14 @@ pub mod resource {
                 type_id: T::resource_id(),
                 value,
                 kinds,
+                extension: None,
             }
         }
 
         pub fn new(
             type_id: &'static str,
             value: String,
-            path: String,
+            path: JsonPointer,
             kinds: Vec<ValidationErrorKind>,
         ) -> Self {
             Self {
@@ -401,8 +692,41 @@ pub mod resource {
                 type_id,
                 value,
                 kinds,
+                extension: None,
             }
         }
+
+        pub fn with_extension(mut self, extension: FieldExtension) -> Self {
+            self.extension = Some(extension);
+            self
+        }
+    }
+
+    #[cfg(test)]
+    mod validation_error_test {
+        use pretty_assertions::assert_eq;
+        use serde_json::json;
+
+        use super::{ValidationError, ValidationErrorKind, ValidationFieldError};
+        use crate::domain::datatype::JsonPointer;
+
+        #[test]
+        fn serialize_nested_field_path() {
+            let error = ValidationError {
+                resource: (),
+                resource_type: "iam::CreateUser",
+                fields: vec![ValidationFieldError::new(
+                    "base::email",
+                    "invalid".into(),
+                    JsonPointer::new(["email"]),
+                    vec![ValidationErrorKind::Pattern("^.+@.+$".into())],
+                )],
+            }
+            .nested("/user");
+
+            let json = serde_json::to_value(&error).expect("Expect to serialize the error");
+            assert_eq!(json["fields"][0]["path"], json!("/user/email"));
+        }
     }
 
     #[derive(Debug, Display, Clone, Error, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -423,11 +747,15 @@ pub mod resource {
         }
     }
 
+    /// Resource addressed by the request path was not found.
+    ///
+    /// Values referenced in the request body are reported as a
+    /// [`ValidationErrorKind::NotFound`] field error instead.
     #[derive(Debug, Display, Clone, Error, PartialEq, Eq, Serialize, Deserialize)]
     #[display(fmt = "Resource {resource_type} not found")]
     pub struct NotFoundError {
-        /// Resource id
-        pub resource_id: Uuid,
+        /// Resource id, when the resource is addressed by id
+        pub resource_id: Option<Uuid>,
         /// Name of the resource
         pub resource_type: &'static str,
     }
@@ -435,7 +763,15 @@ pub mod resource {
     impl NotFoundError {
         pub fn from_resource<T: ResourceID>(id: Uuid) -> Self {
             Self {
-                resource_id: id,
+                resource_id: Some(id),
+                resource_type: T::resource_id(),
+            }
+        }
+
+        /// Resource addressed by other key than its id.
+        pub fn from_resource_type<T: ResourceID>() -> Self {
+            Self {
+                resource_id: None,
                 resource_type: T::resource_id(),
             }
         }
@@ -470,6 +806,10 @@ pub mod security {
         /// Authentication token is invalid.
         #[display(fmt = "invalid_token")]
         InvalidToken,
+
+        /// Authentication token is valid, but issued for a different purpose.
+        #[display(fmt = "invalid_token_payload")]
+        InvalidPayload,
     }
 
     #[derive(Debug, Display, Serialize)]
@@ -488,8 +828,11 @@ pub mod security {
     }
 
     impl From<TokenEncryptionError> for AuthenticationError {
-        fn from(_: TokenEncryptionError) -> Self {
-            Self::InvalidToken
+        fn from(err: TokenEncryptionError) -> Self {
+            match err {
+                TokenEncryptionError::UnexpectedPayload => Self::InvalidPayload,
+                _ => Self::InvalidToken,
+            }
         }
     }
 
@@ -506,14 +849,41 @@ pub mod security {
     }
 }
 
+pub mod operation {
+    use derive_more::Display;
+    use serde::Serialize;
+
+    /// Operation error.
+    ///
+    /// The request is valid, however the operation is not allowed in the current state of the domain.
+    #[derive(Debug, Display, Serialize)]
+    pub enum OperationError {
+        /// The account is deactivated.
+        #[display(fmt = "account_deactivated")]
+        AccountDeactivated,
+        /// The user already follows the maximum number of profiles.
+        #[display(fmt = "follow_limit_reached")]
+        FollowLimitReached,
+    }
+}
+
 pub mod http {
     use derive_more::{Display, Error};
-    use salvo::{http::ParseError, prelude::StatusError, writer::Json, Piece, Response};
+    use std::future::Future;
+
+    use salvo::{
+        http::{ParseError, StatusCode},
+        prelude::StatusError,
+        writer::Json,
+        Piece, Response,
+    };
     use serde::{Deserialize, Serialize};
 
     #[derive(Debug, Display, Clone, Error, Serialize, Deserialize)]
     pub enum BadRequest {
         InvalidContent,
+        /// The id path param is not a valid uuid.
+        InvalidIdParam,
     }
 
     #[derive(Debug, Display, Clone, Error, Serialize, Deserialize)]
@@ -525,18 +895,128 @@ pub mod http {
     }
 
     impl<T> ErrorResponse<T> {
+        /// Create a error response with the title and message in the current [`Locale`].
         pub fn from_status_error(status: &StatusError, err: T) -> Self {
+            let (title, message) = match Locale::current().translate(status.code) {
+                Some((title, message)) => (title.into(), message.into()),
+                None => (
+                    status.name.clone(),
+                    status
+                        .summary
+                        .clone()
+                        .unwrap_or_else(|| status.name.clone()),
+                ),
+            };
+
             Self {
-                title: status.name.clone(),
-                message: status
-                    .summary
-                    .clone()
-                    .unwrap_or_else(|| status.name.clone()),
+                title,
+                message,
                 error: err,
             }
         }
     }
 
+    tokio::task_local! {
+        static LOCALE: Locale;
+    }
+
+    /// Language of the human readable error title and message.
+    ///
+    /// The machine readable `error` of a [`ErrorResponse`] is never translated.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum Locale {
+        #[default]
+        English,
+        Portuguese,
+    }
+
+    impl Locale {
+        /// Select the preferred supported locale of a `Accept-Language` header value.
+        ///
+        /// Falls back to [`Locale::English`] when no supported language is accepted.
+        pub fn from_accept_language(header: &str) -> Self {
+            let mut preferred = (Self::default(), 0.0_f32);
+
+            for entry in header.split(',') {
+                let mut params = entry.split(';');
+                let tag = params.next().unwrap_or_default().trim();
+                let quality = params
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse::<f32>().ok())
+                    .unwrap_or(0.0);
+
+                let primary = tag.split('-').next().unwrap_or_default();
+                let locale = if primary.eq_ignore_ascii_case("en") {
+                    Self::English
+                } else if primary.eq_ignore_ascii_case("pt") {
+                    Self::Portuguese
+                } else {
+                    continue;
+                };
+
+                if quality > preferred.1 {
+                    preferred = (locale, quality);
+                }
+            }
+
+            preferred.0
+        }
+
+        /// Locale of the current request, or the default outside a [`scope`].
+        pub fn current() -> Self {
+            LOCALE.try_with(|locale| *locale).unwrap_or_default()
+        }
+
+        /// Title and message of a status code.
+        ///
+        /// Returns `None` for [`Locale::English`] and untranslated status codes,
+        /// in which case the salvo status error applies.
+        fn translate(self, code: StatusCode) -> Option<(&'static str, &'static str)> {
+            match self {
+                Self::English => None,
+                Self::Portuguese => match code {
+                    StatusCode::BAD_REQUEST => Some((
+                        "Requisição Inválida",
+                        "O servidor não pôde processar a requisição devido a um erro do cliente.",
+                    )),
+                    StatusCode::UNAUTHORIZED => Some((
+                        "Não Autorizado",
+                        "A requisição requer autenticação do usuário.",
+                    )),
+                    StatusCode::FORBIDDEN => {
+                        Some(("Proibido", "O acesso ao recurso requisitado é proibido."))
+                    }
+                    StatusCode::NOT_FOUND => Some((
+                        "Não Encontrado",
+                        "O recurso requisitado não foi encontrado.",
+                    )),
+                    StatusCode::CONFLICT => Some((
+                        "Conflito",
+                        "A requisição conflita com o estado atual do recurso.",
+                    )),
+                    StatusCode::UNPROCESSABLE_ENTITY => Some((
+                        "Entidade Não Processável",
+                        "A requisição não pôde ser processada no estado atual do recurso.",
+                    )),
+                    StatusCode::INTERNAL_SERVER_ERROR => Some((
+                        "Erro Interno do Servidor",
+                        "O servidor encontrou um erro inesperado.",
+                    )),
+                    StatusCode::SERVICE_UNAVAILABLE => Some((
+                        "Serviço Indisponível",
+                        "O servidor está temporariamente indisponível.",
+                    )),
+                    _ => None,
+                },
+            }
+        }
+    }
+
+    /// Run a future rendering the error responses in the `locale`.
+    pub async fn scope<F: Future>(locale: Locale, fut: F) -> F::Output {
+        LOCALE.scope(locale, fut).await
+    }
+
     impl From<ParseError> for BadRequest {
         fn from(_: ParseError) -> Self {
             BadRequest::InvalidContent
@@ -550,4 +1030,37 @@ pub mod http {
             res.set_status_error(status);
         }
     }
+
+    #[cfg(test)]
+    mod locale_test {
+        use pretty_assertions::assert_eq;
+
+        use super::Locale;
+
+        #[test]
+        fn select_supported_language() {
+            assert_eq!(Locale::from_accept_language("pt-BR"), Locale::Portuguese);
+            assert_eq!(
+                Locale::from_accept_language("en-US,en;q=0.9"),
+                Locale::English
+            );
+            assert_eq!(
+                Locale::from_accept_language("fr-FR, pt;q=0.8, en;q=0.5"),
+                Locale::Portuguese
+            );
+        }
+
+        #[test]
+        fn fallback_to_english() {
+            assert_eq!(
+                Locale::from_accept_language("fr-FR, de;q=0.5"),
+                Locale::English
+            );
+            assert_eq!(Locale::from_accept_language(""), Locale::English);
+            assert_eq!(
+                Locale::from_accept_language("pt;q=invalid"),
+                Locale::English
+            );
+        }
+    }
 }
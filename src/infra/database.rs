@@ -3,11 +3,14 @@ pub mod connection {
 
     use crate::config::env_var;
 
+    pub const MIN_CONNECTIONS: u32 = 1;
+    pub const MAX_CONNECTIONS: u32 = 5;
+
     pub async fn create_sqlx_pool() -> sqlx::PgPool {
         let dburl = env_var::get().database_url.clone();
         sqlx::postgres::PgPoolOptions::new()
-            .min_connections(1)
-            .max_connections(5)
+            .min_connections(MIN_CONNECTIONS)
+            .max_connections(MAX_CONNECTIONS)
             .acquire_timeout(Duration::from_millis(1000))
             .idle_timeout(Duration::from_millis(1000 * 30))
             .max_lifetime(Duration::from_millis(1000 * 10))
@@ -18,6 +21,8 @@ pub mod connection {
 }
 
 mod sql {
+    use std::{future::Future, io};
+
     use sqlx::{Database, Encode, QueryBuilder, Type};
 
     pub fn push_list<'args, I, T, DB>(qb: &mut QueryBuilder<'args, DB>, list: I)
@@ -33,34 +38,295 @@ mod sql {
         }
         sep.push_unseparated(")");
     }
+
+    /// Maximum number of retries of a read query.
+    pub const MAX_READ_RETRIES: u32 = 1;
+
+    /// Connection dropped while the query was in flight.
+    pub fn is_connection_reset(err: &sqlx::Error) -> bool {
+        match err {
+            sqlx::Error::Io(err) => matches!(
+                err.kind(),
+                io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::UnexpectedEof
+            ),
+            _ => false,
+        }
+    }
+
+    /// Run a idempotent read query, retrying when the connection is reset mid query.
+    ///
+    /// Must not be used with writes, since a reset connection does not tell
+    /// whether the statement was applied or not.
+    pub async fn retry_read<T, F, Fut>(mut query: F) -> Result<T, sqlx::Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, sqlx::Error>>,
+    {
+        let mut retries = 0;
+        loop {
+            match query().await {
+                Err(err) if retries < MAX_READ_RETRIES && is_connection_reset(&err) => {
+                    retries += 1;
+                    tracing::warn!(target = "database", retry = retries, cause = %err);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Run a write query once, surfacing a connection reset instead of retrying.
+    ///
+    /// The statement may have been applied before the connection was lost, so the
+    /// caller decides how to recover.
+    pub async fn run_write<T, Fut>(query: Fut) -> Result<T, sqlx::Error>
+    where
+        Fut: Future<Output = Result<T, sqlx::Error>>,
+    {
+        let result = query.await;
+        if let Err(err) = &result {
+            if is_connection_reset(err) {
+                tracing::warn!(target = "database", cause = %err, "write not retried");
+            }
+        }
+        result
+    }
+}
+
+/// Resource fields guarded by the schema unique constraints.
+pub mod constraint {
+    use crate::error::persistence::PersistenceError;
+
+    /// SQLSTATE of a unique constraint violation.
+    pub const UNIQUE_VIOLATION: &str = "23505";
+
+    /// Resource field guarded by a unique constraint.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct UniqueField {
+        /// Value type id
+        pub type_id: &'static str,
+        /// Field path in the resource
+        pub path: &'static str,
+    }
+
+    /// Unique constraints of the schema, by name.
+    ///
+    /// A new unique constraint is wired here, without changing the translation.
+    pub const UNIQUE_FIELDS: &[(&str, UniqueField)] = &[
+        (
+            "user_unique_email",
+            UniqueField {
+                type_id: "base::email",
+                path: "/email",
+            },
+        ),
+        (
+            "user_unique_username",
+            UniqueField {
+                type_id: "base::username",
+                path: "/username",
+            },
+        ),
+    ];
+
+    pub fn unique_field(constraint: &str) -> Option<UniqueField> {
+        UNIQUE_FIELDS
+            .iter()
+            .find(|(name, _)| *name == constraint)
+            .map(|(_, field)| *field)
+    }
+
+    /// Field of the unique constraint violated by `err`.
+    pub fn violated_unique_field(err: &PersistenceError) -> Option<UniqueField> {
+        match err {
+            PersistenceError::Database(Some(code), detail) if code == UNIQUE_VIOLATION => {
+                detail.constraint.as_deref().and_then(unique_field)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod constraint_test {
+    use pretty_assertions::assert_eq;
+
+    use super::constraint::{self, UniqueField};
+    use crate::error::persistence::{DatabaseErrorDetail, PersistenceError};
+
+    #[test]
+    fn map_user_constraints_to_fields() {
+        assert_eq!(
+            constraint::unique_field("user_unique_email"),
+            Some(UniqueField {
+                type_id: "base::email",
+                path: "/email",
+            })
+        );
+        assert_eq!(
+            constraint::unique_field("user_unique_username"),
+            Some(UniqueField {
+                type_id: "base::username",
+                path: "/username",
+            })
+        );
+        assert_eq!(constraint::unique_field("user_pk"), None);
+    }
+
+    #[test]
+    fn translate_unique_violation() {
+        let violation = |code: &str, constraint: &str| {
+            PersistenceError::Database(
+                Some(code.into()),
+                DatabaseErrorDetail {
+                    constraint: Some(constraint.into()),
+                    message: String::new(),
+                },
+            )
+        };
+
+        assert_eq!(
+            constraint::violated_unique_field(&violation("23505", "user_unique_username"))
+                .map(|field| field.path),
+            Some("/username")
+        );
+        assert_eq!(
+            constraint::violated_unique_field(&violation("23503", "user_unique_username")),
+            None
+        );
+        assert_eq!(
+            constraint::violated_unique_field(&PersistenceError::NotFound),
+            None
+        );
+    }
+}
+
+#[cfg(test)]
+mod sql_test {
+    use std::io;
+
+    use pretty_assertions::assert_eq;
+
+    use super::sql;
+
+    fn connection_reset() -> sqlx::Error {
+        sqlx::Error::Io(io::ErrorKind::ConnectionReset.into())
+    }
+
+    #[tokio::test]
+    async fn retry_read_on_connection_reset() {
+        let mut calls = 0;
+        let result = sql::retry_read(|| {
+            calls += 1;
+            let call = calls;
+            async move {
+                if call == 1 {
+                    return Err(connection_reset());
+                }
+                Ok(call)
+            }
+        })
+        .await;
+
+        assert_eq!(result.expect("Expect the retried read to succeed"), 2);
+        assert_eq!(calls, 2);
+    }
+
+    #[tokio::test]
+    async fn bound_read_retries() {
+        let mut calls: u32 = 0;
+        let result: Result<(), _> = sql::retry_read(|| {
+            calls += 1;
+            async { Err(connection_reset()) }
+        })
+        .await;
+
+        assert!(sql::is_connection_reset(
+            &result.expect_err("Expect the read to fail after the retries")
+        ));
+        assert_eq!(calls, 1 + sql::MAX_READ_RETRIES);
+    }
+
+    #[tokio::test]
+    async fn not_retry_other_errors() {
+        let mut calls = 0;
+        let result: Result<(), _> = sql::retry_read(|| {
+            calls += 1;
+            async { Err(sqlx::Error::RowNotFound) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn surface_write_connection_reset() {
+        let mut calls = 0;
+        let mut write = || {
+            calls += 1;
+            async { Err::<(), _>(connection_reset()) }
+        };
+
+        let result = sql::run_write(write()).await;
+
+        assert!(sql::is_connection_reset(
+            &result.expect_err("Expect the write to surface the reset")
+        ));
+        assert_eq!(calls, 1);
+    }
 }
 
 pub mod repository {
     use std::collections::HashSet;
 
     use futures::TryStreamExt;
-    use sqlx::{FromRow, PgPool, QueryBuilder, Row};
+    use sqlx::{FromRow, PgExecutor, PgPool, Postgres, QueryBuilder, Row};
     use tracing::instrument;
     use uuid::Uuid;
 
     use super::sql;
     use crate::{
         app::resource::iam::UserResponse,
-        domain::entity::{iam::User, Entity},
+        domain::{
+            datatype::audit::AuditEvent,
+            entity::{iam::User, profile::Follow, Entity},
+        },
         error::{
             persistence::{MutationError, PersistenceError},
             resource::ConflictError,
         },
     };
 
-    #[instrument(target = "database::iam::user", skip(pool))]
-    pub async fn insert_users<'u, I>(pool: &PgPool, users: I) -> Result<(), MutationError>
+    /// Columns of `iam.user` changed by a update, in bind order.
+    macro_rules! user_mutable_columns {
+        () => {
+            "updated, version, username, email, password_hash, bio, image_url, deactivated"
+        };
+    }
+
+    /// Columns of `iam.user`, in bind order.
+    ///
+    /// Single source of the user column set, referenced by every user query.
+    macro_rules! user_columns {
+        () => {
+            concat!("id, created, ", user_mutable_columns!())
+        };
+    }
+
+    const UPDATE_USER: &str = concat!(
+        "UPDATE iam.user SET (",
+        user_mutable_columns!(),
+        ") = ($1, $2, $3, $4, $5, $6, $7, $8) WHERE id = $9 AND version = $10"
+    );
+
+    fn insert_users_query<'u, I>(users: I) -> QueryBuilder<'u, Postgres>
     where
-        I: IntoIterator<Item = &'u User> + std::fmt::Debug,
+        I: IntoIterator<Item = &'u User>,
     {
-        let mut qb = QueryBuilder::new(
-            "INSERT INTO iam.user (id, created, updated, version, username, email, password_hash, bio, image_url) "
-        );
+        let mut qb = QueryBuilder::new(concat!("INSERT INTO iam.user (", user_columns!(), ") "));
         qb.push_values(users, |mut qb, user| {
             qb.push_bind(user.ident());
             qb.push_bind(user.created());
@@ -71,39 +337,117 @@ pub mod repository {
             qb.push_bind(user.password_hash().to_string());
             qb.push_bind(user.bio());
             qb.push_bind(user.image_url().clone().map(|url| url.to_string()));
+            qb.push_bind(*user.deactivated());
         });
-        qb.push(" ON CONFLICT (id) DO NOTHING");
+        qb.push(" ON CONFLICT (id) DO NOTHING RETURNING id");
+        qb
+    }
 
-        let afected = qb
-            .build()
-            .execute(pool)
-            .await
-            .map_err(PersistenceError::from)?;
+    /// Insert the users.
+    ///
+    /// Fail with a [`ConflictError`] of the first user whose id already exists.
+    #[instrument(target = "database::iam::user", skip(executor))]
+    pub async fn insert_users<'e, 'u, E, I>(executor: E, users: I) -> Result<(), MutationError>
+    where
+        E: PgExecutor<'e>,
+        I: IntoIterator<Item = &'u User> + Clone + std::fmt::Debug,
+    {
+        let inserted: HashSet<Uuid> = sql::run_write(
+            insert_users_query(users.clone())
+                .build()
+                .fetch_all(executor),
+        )
+        .await
+        .map_err(PersistenceError::from)?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
 
-        if afected.rows_affected() == 0 {
-            return Err(ConflictError::from_resource::<UserResponse>(None).into());
+        if let Some(user) = users
+            .into_iter()
+            .find(|user| !inserted.contains(&user.ident()))
+        {
+            return Err(ConflictError::from_resource::<UserResponse>(Some(user.ident())).into());
         }
 
         Ok(())
     }
 
+    /// Email and username already registered.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct ConflictingIdentifiers {
+        pub email: bool,
+        pub username: bool,
+    }
+
+    impl ConflictingIdentifiers {
+        pub fn any(&self) -> bool {
+            self.email || self.username
+        }
+    }
+
+    /// Check the email and the case-insensitive username in a single query.
+    #[instrument(target = "database::iam::user", skip(executor))]
+    pub async fn conflicting_identifiers<'e, E>(
+        executor: E,
+        email: &str,
+        username: &str,
+    ) -> Result<ConflictingIdentifiers, PersistenceError>
+    where
+        E: PgExecutor<'e>,
+    {
+        let (email, username): (bool, bool) = sqlx::query_as(concat!(
+            "SELECT coalesce(bool_or(lower(email) = $1), false), ",
+            "coalesce(bool_or(lower(username) = $2), false) ",
+            "FROM iam.user WHERE lower(email) = $1 OR lower(username) = $2"
+        ))
+        .bind(canonical_email(email))
+        .bind(canonical_username(username))
+        .fetch_one(executor)
+        .await?;
+
+        Ok(ConflictingIdentifiers { email, username })
+    }
+
+    /// Insert a user, unless its email or username is already registered.
+    ///
+    /// The check and the insert run in one transaction. A concurrent registration
+    /// may still take the identifiers in between, failing the insert with a unique
+    /// violation, see [`super::constraint`].
+    #[instrument(target = "database::iam::user", skip(pool))]
+    pub async fn create_user(
+        pool: &PgPool,
+        user: &User,
+    ) -> Result<ConflictingIdentifiers, MutationError> {
+        let mut trx = pool.begin().await.map_err(PersistenceError::from)?;
+
+        let conflicts = conflicting_identifiers(&mut trx, user.email(), user.username()).await?;
+        if conflicts.any() {
+            return Ok(conflicts);
+        }
+
+        insert_users(&mut trx, [user]).await?;
+        trx.commit().await.map_err(PersistenceError::from)?;
+
+        Ok(conflicts)
+    }
+
     #[instrument(target = "database::iam::user", skip(pool))]
     pub async fn update_user<'u>(pool: &PgPool, user: &'u User) -> Result<(), MutationError> {
-        let afected = sqlx::query(concat!(
-            "UPDATE TABLE iam.user SET updated = $1, version = $2, username = $3, ",
-            "email = $4, password_hash = $5, bio = $6, image_url = $7 ",
-            "WHERE id = $8 AND version = $9"
-        ))
-        .bind(user.updated())
-        .bind(user.version() as i64)
-        .bind(user.username())
-        .bind(user.email())
-        .bind(user.password_hash().to_string())
-        .bind(user.bio())
-        .bind(user.image_url().clone().map(|url| url.to_string()))
-        .bind(user.ident())
-        .bind(user.version() as i64 - 1)
-        .execute(pool)
+        let afected = sql::run_write(
+            sqlx::query(UPDATE_USER)
+                .bind(user.updated())
+                .bind(user.version() as i64)
+                .bind(user.username())
+                .bind(user.email())
+                .bind(user.password_hash().to_string())
+                .bind(user.bio())
+                .bind(user.image_url().clone().map(|url| url.to_string()))
+                .bind(*user.deactivated())
+                .bind(user.ident())
+                .bind(user.version() as i64 - 1)
+                .execute(pool),
+        )
         .await
         .map_err(PersistenceError::from)?;
 
@@ -119,12 +463,49 @@ pub mod repository {
         pool: &PgPool,
         email: String,
     ) -> Result<Option<User>, PersistenceError> {
-        let row = sqlx::query(concat!(
-            "SELECT id, created, updated, version, username, email, password_hash, ",
-            "bio, image_url FROM iam.user WHERE email = $1",
-        ))
-        .bind(email)
-        .fetch_optional(pool)
+        let row = sql::retry_read(|| {
+            sqlx::query(concat!(
+                "SELECT ",
+                user_columns!(),
+                " FROM iam.user WHERE lower(email) = $1"
+            ))
+            .bind(canonical_email(&email))
+            .fetch_optional(pool)
+        })
+        .await?;
+
+        if let Some(row) = row {
+            return Ok(Some(User::from_row(&row)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Canonical form of a username, compared case-insensitively.
+    ///
+    /// The username is stored in its display form.
+    fn canonical_username(username: &str) -> String {
+        username.to_lowercase()
+    }
+
+    fn canonical_email(email: &str) -> String {
+        email.to_lowercase()
+    }
+
+    #[instrument(target = "database::iam::user", skip(pool))]
+    pub async fn find_user_by_username(
+        pool: &PgPool,
+        username: String,
+    ) -> Result<Option<User>, PersistenceError> {
+        let row = sql::retry_read(|| {
+            sqlx::query(concat!(
+                "SELECT ",
+                user_columns!(),
+                " FROM iam.user WHERE lower(username) = $1"
+            ))
+            .bind(canonical_username(&username))
+            .fetch_optional(pool)
+        })
         .await?;
 
         if let Some(row) = row {
@@ -139,12 +520,15 @@ pub mod repository {
         pool: &PgPool,
         id: Uuid,
     ) -> Result<Option<User>, PersistenceError> {
-        let row = sqlx::query(concat!(
-            "SELECT id, created, updated, version, username, email, password_hash, ",
-            "bio, image_url FROM iam.user WHERE id = $1",
-        ))
-        .bind(id)
-        .fetch_optional(pool)
+        let row = sql::retry_read(|| {
+            sqlx::query(concat!(
+                "SELECT ",
+                user_columns!(),
+                " FROM iam.user WHERE id = $1"
+            ))
+            .bind(id)
+            .fetch_optional(pool)
+        })
         .await?;
 
         if let Some(row) = row {
@@ -180,7 +564,16 @@ pub mod repository {
     where
         I: IntoIterator<Item = &'a String> + std::fmt::Debug,
     {
-        query_column_list!(pool, values, "SELECT email FROM iam.user WHERE email IN ").await
+        let values: Vec<String> = values
+            .into_iter()
+            .map(|email| canonical_email(email))
+            .collect();
+        query_column_list!(
+            pool,
+            values,
+            "SELECT email FROM iam.user WHERE lower(email) IN "
+        )
+        .await
     }
 
     #[instrument(skip(pool))]
@@ -191,11 +584,254 @@ pub mod repository {
     where
         I: IntoIterator<Item = &'a String> + std::fmt::Debug,
     {
+        let values: Vec<String> = values
+            .into_iter()
+            .map(|username| canonical_username(username))
+            .collect();
         query_column_list!(
             pool,
             values,
-            "SELECT username FROM iam.user WHERE username IN "
+            "SELECT username FROM iam.user WHERE lower(username) IN "
         )
         .await
     }
+
+    #[instrument(target = "database::iam::audit_log", skip(pool))]
+    pub async fn insert_audit_event(
+        pool: &PgPool,
+        event: &AuditEvent,
+    ) -> Result<(), PersistenceError> {
+        sql::run_write(
+            sqlx::query(concat!(
+                "INSERT INTO iam.audit_log (id, time, action, actor_id, target, ip) ",
+                "VALUES ($1, $2, $3, $4, $5, $6)"
+            ))
+            .bind(event.id)
+            .bind(event.time)
+            .bind(event.action.as_str())
+            .bind(event.actor)
+            .bind(event.target.as_deref())
+            .bind(event.ip.map(|ip| ip.to_string()))
+            .execute(pool),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Insert a follow, returning whether the follower was not following the user already.
+    #[instrument(target = "database::iam::follows", skip(pool))]
+    pub async fn insert_follow(pool: &PgPool, follow: &Follow) -> Result<bool, PersistenceError> {
+        let inserted = sql::run_write(
+            sqlx::query(concat!(
+                "INSERT INTO iam.follows ",
+                "(id, created, updated, version, follower_id, following_id) ",
+                "VALUES ($1, $2, $3, $4, $5, $6) ",
+                "ON CONFLICT (follower_id, following_id) DO NOTHING"
+            ))
+            .bind(follow.ident())
+            .bind(follow.created())
+            .bind(follow.updated())
+            .bind(follow.version() as i64)
+            .bind(follow.follower_id())
+            .bind(follow.following_id())
+            .execute(pool),
+        )
+        .await?;
+
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    /// Delete the follow, returning whether the follower was following the user.
+    #[instrument(target = "database::iam::follows", skip(pool))]
+    pub async fn delete_follow(
+        pool: &PgPool,
+        follower_id: Uuid,
+        following_id: Uuid,
+    ) -> Result<bool, PersistenceError> {
+        let deleted = sql::run_write(
+            sqlx::query("DELETE FROM iam.follows WHERE follower_id = $1 AND following_id = $2")
+                .bind(follower_id)
+                .bind(following_id)
+                .execute(pool),
+        )
+        .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// Number of profiles the follower follows.
+    #[instrument(target = "database::iam::follows", skip(pool))]
+    pub async fn count_following(
+        pool: &PgPool,
+        follower_id: Uuid,
+    ) -> Result<u64, PersistenceError> {
+        let count: i64 = sql::retry_read(|| {
+            sqlx::query_scalar("SELECT count(*) FROM iam.follows WHERE follower_id = $1")
+                .bind(follower_id)
+                .fetch_one(pool)
+        })
+        .await?;
+
+        Ok(count as u64)
+    }
+
+    /// Ids of `ids` the follower follows, resolving the flag of a page of profiles at once.
+    #[instrument(target = "database::iam::follows", skip(pool))]
+    pub async fn following_ids(
+        pool: &PgPool,
+        follower_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<HashSet<Uuid>, PersistenceError> {
+        let following: Vec<Uuid> = sql::retry_read(|| {
+            sqlx::query_scalar(concat!(
+                "SELECT following_id FROM iam.follows ",
+                "WHERE follower_id = $1 AND following_id = ANY($2)"
+            ))
+            .bind(follower_id)
+            .bind(ids)
+            .fetch_all(pool)
+        })
+        .await?;
+
+        Ok(following.into_iter().collect())
+    }
+
+    /// Usernames of the active users the user follows, ordered by username.
+    #[instrument(target = "database::iam::follows", skip(pool))]
+    pub async fn find_following_usernames(
+        pool: &PgPool,
+        follower_id: Uuid,
+    ) -> Result<Vec<String>, PersistenceError> {
+        let usernames: Vec<String> = sql::retry_read(|| {
+            sqlx::query_scalar(concat!(
+                "SELECT u.username FROM iam.follows f ",
+                "JOIN iam.user u ON u.id = f.following_id ",
+                "WHERE f.follower_id = $1 AND NOT u.deactivated ORDER BY u.username"
+            ))
+            .bind(follower_id)
+            .fetch_all(pool)
+        })
+        .await?;
+
+        Ok(usernames)
+    }
+
+    /// Usernames of the active users following the user, ordered by username.
+    #[instrument(target = "database::iam::follows", skip(pool))]
+    pub async fn find_follower_usernames(
+        pool: &PgPool,
+        following_id: Uuid,
+    ) -> Result<Vec<String>, PersistenceError> {
+        let usernames: Vec<String> = sql::retry_read(|| {
+            sqlx::query_scalar(concat!(
+                "SELECT u.username FROM iam.follows f ",
+                "JOIN iam.user u ON u.id = f.follower_id ",
+                "WHERE f.following_id = $1 AND NOT u.deactivated ORDER BY u.username"
+            ))
+            .bind(following_id)
+            .fetch_all(pool)
+        })
+        .await?;
+
+        Ok(usernames)
+    }
+
+    /// Tables the application requires, as `schema.table`.
+    pub const REQUIRED_TABLES: [&str; 3] = ["iam.user", "iam.audit_log", "iam.follows"];
+
+    /// Required tables absent from the database, as in a partially migrated schema.
+    #[instrument(target = "database::schema", skip(pool))]
+    pub async fn missing_tables(pool: &PgPool) -> Result<Vec<&'static str>, PersistenceError> {
+        let required: Vec<&str> = REQUIRED_TABLES.to_vec();
+        let found: HashSet<String> = sqlx::query_scalar(concat!(
+            "SELECT table_schema || '.' || table_name FROM information_schema.tables ",
+            "WHERE table_schema || '.' || table_name = ANY($1)"
+        ))
+        .bind(&required)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+        Ok(required
+            .into_iter()
+            .filter(|table| !found.contains(*table))
+            .collect())
+    }
+
+    #[cfg(test)]
+    mod user_columns_test {
+        use std::str::FromStr;
+
+        use pretty_assertions::assert_eq;
+        use uuid::Uuid;
+
+        use super::{insert_users_query, UPDATE_USER};
+        use crate::domain::{datatype::security::PasswordHash, entity::iam::User};
+
+        fn columns(list: &str) -> Vec<&str> {
+            list.split(", ").collect()
+        }
+
+        fn placeholders(sql: &str) -> usize {
+            sql.matches('$').count()
+        }
+
+        #[test]
+        fn user_column_set() {
+            assert_eq!(
+                columns(user_columns!()),
+                vec![
+                    "id",
+                    "created",
+                    "updated",
+                    "version",
+                    "username",
+                    "email",
+                    "password_hash",
+                    "bio",
+                    "image_url",
+                    "deactivated",
+                ]
+            );
+
+            assert_eq!(
+                columns(user_columns!())[2..].to_vec(),
+                columns(user_mutable_columns!())
+            );
+        }
+
+        #[test]
+        fn insert_binds_every_column() {
+            let hash = PasswordHash::from_str(
+                "$argon2id$v=19$m=16,t=3,p=1$TE1LcnNPbTVEcnNQYTBPUA$2JYnsTwG5Zu17cIWiaAxnA",
+            )
+            .expect("Expect a valid password hash");
+            let user = User::new(
+                Uuid::new_v4(),
+                "user@email.com".into(),
+                "user12345".into(),
+                hash,
+            );
+
+            let qb = insert_users_query([&user]);
+
+            assert_eq!(placeholders(qb.sql()), columns(user_columns!()).len());
+        }
+
+        #[test]
+        fn update_user_table() {
+            assert!(UPDATE_USER.starts_with("UPDATE iam.user SET ("));
+        }
+
+        #[test]
+        fn update_binds_every_mutable_column() {
+            // plus the id and the expected version on the WHERE clause
+            assert_eq!(
+                placeholders(UPDATE_USER),
+                columns(user_mutable_columns!()).len() + 2
+            );
+        }
+    }
 }
@@ -12,40 +12,137 @@ pub mod router {
 
     use super::{
         controller::*,
-        service::{Argon2HashService, JWTEncryptionService},
+        service::{CompositeHashService, DatabaseAuditSink, JWTEncryptionService},
     };
+    use crate::config::env_var;
 
     pub fn app(
         pool: &PgPool,
-        hash_service: Arc<Argon2HashService>,
+        hash_service: Arc<CompositeHashService>,
         token_service: Arc<JWTEncryptionService>,
+        audit_sink: Arc<DatabaseAuditSink>,
     ) -> Router {
         PathFilter::register_wisp_regex(
             "uuid",
             regex::Regex::new(
-                "^[0-9A-F]{8}-[0-9A-F]{4}-4[0-9A-F]{3}-[89AB][0-9A-F]{3}-[0-9A-F]{12}$",
+                "(?i)^[0-9A-F]{8}-[0-9A-F]{4}-4[0-9A-F]{3}-[89AB][0-9A-F]{3}-[0-9A-F]{12}$",
             )
             .expect("Expect a valid uuid v4 regex"),
         );
 
-        Router::new()
+        let mut router = Router::new();
+        // only a asymmetric service has public keys to publish
+        if !token_service.jwk_set().keys.is_empty() {
+            router = router.push(
+                Router::with_path(".well-known/jwks.json")
+                    .get(JwksController::new(token_service.clone())),
+            );
+        }
+
+        router
             .push(
                 Router::with_path("api")
+                    .push(
+                        Router::with_path("user/export")
+                            .hoop(RequireAuth::new(token_service.clone()))
+                            .get(ExportUserController::new(pool.clone())),
+                    )
+                    .push(
+                        Router::with_path("user/reactivate").post(ReactivateUserController::new(
+                            pool.clone(),
+                            hash_service.clone(),
+                            env_var::get().registration_conceal_email,
+                        )),
+                    )
+                    .push(
+                        Router::with_path("user/me/deactivate")
+                            .hoop(RequireAuth::new(token_service.clone()))
+                            .post(DeactivateUserController::new(
+                                pool.clone(),
+                                audit_sink.clone(),
+                            )),
+                    )
+                    .push(
+                        Router::with_path("user/me")
+                            .hoop(RequireAuth::new(token_service.clone()))
+                            .put(UpdateUserController::new(pool.clone())),
+                    )
+                    .push(
+                        Router::with_path("user/<id:uuid>/deactivate")
+                            .hoop(RequireAuth::new(token_service.clone()))
+                            .post(DeactivateUserController::new(
+                                pool.clone(),
+                                audit_sink.clone(),
+                            )),
+                    )
                     .push(
                         Router::with_path("user/<id:uuid>")
                             .post(CreateUserController::new(
                                 pool.clone(),
                                 hash_service.clone(),
+                                env_var::get().registration_conceal_email,
                             ))
-                            .put(UpdateUserController::new(
+                            .push(
+                                Router::new()
+                                    .hoop(RequireAuth::new(token_service.clone()))
+                                    .put(UpdateUserController::new(pool.clone())),
+                            ),
+                    )
+                    .push(
+                        Router::with_path("profiles/<username>/follow")
+                            .hoop(RequireAuth::new(token_service.clone()))
+                            .post(FollowProfileController::new(
+                                pool.clone(),
+                                audit_sink.clone(),
+                                env_var::get().follow_limit,
+                            ))
+                            .delete(UnfollowProfileController::new(
                                 pool.clone(),
-                                token_service.clone(),
+                                audit_sink.clone(),
                             )),
                     )
+                    .push(
+                        Router::with_path("profile/following-status")
+                            .hoop(RequireAuth::new(token_service.clone()))
+                            .post(FollowingStatusController::new(pool.clone())),
+                    )
+                    .push(
+                        Router::with_path("profiles/me")
+                            .hoop(RequireAuth::new(token_service.clone()))
+                            .get(GetProfileController::new(pool.clone())),
+                    )
+                    .push(
+                        Router::with_path("profiles/<username>")
+                            .hoop(RequireAuth::optional(token_service.clone()))
+                            .get(GetProfileController::new(pool.clone())),
+                    )
+                    .push(Router::with_path("articles/slugify").get(PreviewSlugController))
+                    .push(
+                        Router::with_path("auth/introspect")
+                            .get(IntrospectTokenController::new(token_service.clone())),
+                    )
+                    .push(
+                        Router::with_path("auth/refresh").post(RefreshTokenController::new(
+                            pool.clone(),
+                            token_service.clone(),
+                        )),
+                    )
                     .push(Router::with_path("auth/<id:uuid>").post(
-                        AuthenticateUserController::new(pool.clone(), hash_service, token_service),
+                        AuthenticateUserController::new(
+                            pool.clone(),
+                            hash_service,
+                            token_service,
+                            audit_sink,
+                            env_var::get().registration_conceal_email,
+                        ),
                     )),
             )
+            .push(Router::with_path("ready").get(ReadinessController::new(pool.clone())))
+            .push(Router::with_path("<**>").options(Preflight))
             .hoop(Logger)
+            .hoop(SecurityHeaders::from_config())
+            .hoop(Localization)
+            .hoop(CatchPanic)
+            .hoop(Cors::from_config())
     }
 }
@@ -1,12 +1,27 @@
 use argon2::{Algorithm, Argon2, Params, Version};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
-use serde::{de::DeserializeOwned, Serialize};
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+use jsonwebtoken::{errors::ErrorKind, DecodingKey, EncodingKey, Header, Validation};
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Serialize,
+};
+use sqlx::PgPool;
 
-use crate::domain::datatype::security::{
-    OutputHash, PasswordHash, PasswordHashAlgorithm, PasswordHashError, SaltString,
-    TokenEncryptionError, TokenIssuer, TokenPayload,
+use crate::domain::datatype::{
+    audit::AuditEvent,
+    security::{
+        OutputHash, PasswordHash, PasswordHashAlgorithm, PasswordHashError, PasswordParams,
+        SaltString, TokenEncryptionError, TokenIssuer, TokenPayload,
+    },
 };
-use crate::domain::service::{PasswordHashService, TokenEncryptionService};
+use crate::domain::service::{AuditSink, PasswordHashService, TokenEncryptionService};
+use crate::infra::database::repository;
 
 pub struct Argon2HashService(Argon2<'static>);
 
@@ -15,19 +30,76 @@ impl Argon2HashService {
     pub const VERSION: u32 = Version::V0x13 as u32;
     pub const HASH_OUTPUT_LENGTH: usize = Params::DEFAULT_OUTPUT_LEN;
 
+    /// Memory cost limit of the autotune, in KiB.
+    pub const AUTOTUNE_MAX_M_COST: u32 = 256 * 1024;
+
     pub fn new() -> Self {
-        Self(Argon2::new(
-            Algorithm::Argon2id,
-            Version::V0x13,
-            Params::new(
-                Params::DEFAULT_M_COST,
-                Params::DEFAULT_T_COST,
-                Params::DEFAULT_P_COST,
-                Some(Self::HASH_OUTPUT_LENGTH),
-            )
-            .expect("Expect valid default Argon2 params"),
+        Self::with_params(Self::build_params(
+            Params::DEFAULT_M_COST,
+            Params::DEFAULT_T_COST,
         ))
     }
+
+    pub fn with_params(params: Params) -> Self {
+        Self(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    pub fn params(&self) -> &Params {
+        self.0.params()
+    }
+
+    fn build_params(m_cost: u32, t_cost: u32) -> Params {
+        Params::new(
+            m_cost,
+            t_cost,
+            Params::DEFAULT_P_COST,
+            Some(Self::HASH_OUTPUT_LENGTH),
+        )
+        .expect("Expect valid Argon2 params")
+    }
+
+    /// Time to hash a password with `params` on this host.
+    pub fn measure(params: Params) -> Duration {
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        let salt = SaltString::generate(&mut rand_core::OsRng);
+        let mut buf = [0; Self::HASH_OUTPUT_LENGTH];
+
+        let start = Instant::now();
+        argon2
+            .hash_password_into(b"autotune_password", salt.as_bytes(), &mut buf)
+            .expect("Expect to hash the autotune password");
+        start.elapsed()
+    }
+
+    /// Calibrate the Argon2 params, so a hash takes approximately `target` on this host.
+    pub fn autotune(target: Duration) -> Params {
+        Self::autotune_with(target, Self::measure)
+    }
+
+    /// Calibrate the Argon2 params against the hash time `measure` reports.
+    ///
+    /// The memory cost is raised while a single pass fits in half the target, then the
+    /// passes fill the remaining time. The params never go below the Argon2 defaults,
+    /// a slow host gets a slower hash rather than a weaker one.
+    fn autotune_with<F>(target: Duration, mut measure: F) -> Params
+    where
+        F: FnMut(Params) -> Duration,
+    {
+        let mut m_cost = Params::DEFAULT_M_COST;
+
+        while m_cost * 2 <= Self::AUTOTUNE_MAX_M_COST
+            && measure(Self::build_params(m_cost * 2, 1)) * 2 <= target
+        {
+            m_cost *= 2;
+        }
+
+        let pass = measure(Self::build_params(m_cost, 1));
+        let t_cost = (target.as_secs_f64() / pass.as_secs_f64())
+            .round()
+            .max(Params::DEFAULT_T_COST as f64) as u32;
+
+        Self::build_params(m_cost, t_cost)
+    }
 }
 
 impl PasswordHashService for Argon2HashService {
@@ -68,14 +140,27 @@ impl PasswordHashService for Argon2HashService {
 
         Err(PasswordHashError::InvalidPassword)
     }
+
+    fn algorithm(&self) -> PasswordHashAlgorithm {
+        Self::ALGORITHM
+    }
+
+    fn hash_params(&self) -> PasswordParams {
+        self.0
+            .params()
+            .try_into()
+            .expect("Expect the Argon2 params to fit in a ParamsString")
+    }
 }
 
 #[cfg(test)]
 mod argon2_hash_service_test {
-    use std::str::FromStr;
+    use std::{str::FromStr, time::Duration};
 
     use pretty_assertions::assert_eq;
 
+    use argon2::Params;
+
     use super::Argon2HashService;
     use crate::domain::{datatype::security::PasswordHash, service::PasswordHashService};
 
@@ -102,43 +187,765 @@ mod argon2_hash_service_test {
             assert_eq!(argon2.verify_password(pwd, &deserialized), Ok(()));
         }
     }
+
+    /// Hash time growing with the memory and time costs, 1µs per KiB pass.
+    fn linear_cost(params: Params) -> Duration {
+        Duration::from_micros(params.m_cost() as u64 * params.t_cost() as u64)
+    }
+
+    #[test]
+    fn autotune_to_target_duration() {
+        let params = Argon2HashService::autotune_with(Duration::from_millis(250), linear_cost);
+
+        assert_eq!((params.m_cost(), params.t_cost()), (64 * 1024, 4));
+    }
+
+    #[test]
+    fn autotune_not_below_default_params() {
+        let params = Argon2HashService::autotune_with(Duration::from_millis(250), |params| {
+            linear_cost(params) * 1000
+        });
+
+        assert_eq!(
+            (params.m_cost(), params.t_cost()),
+            (Params::DEFAULT_M_COST, Params::DEFAULT_T_COST)
+        );
+
+        let argon2 = Argon2HashService::with_params(params);
+        let hash = argon2
+            .hash_password("12345678")
+            .expect("Expect to hash the password");
+        assert_eq!(argon2.verify_password("12345678", &hash), Ok(()));
+    }
+
+    #[test]
+    fn autotune_up_to_max_memory_cost() {
+        let params = Argon2HashService::autotune_with(Duration::from_millis(250), |params| {
+            linear_cost(params) / 1000
+        });
+
+        assert_eq!(params.m_cost(), Argon2HashService::AUTOTUNE_MAX_M_COST);
+        assert!(params.t_cost() > Params::DEFAULT_T_COST);
+    }
+
+    #[test]
+    fn rehash_outdated_params() {
+        let outdated = Argon2HashService::with_params(
+            Params::new(16, 1, 1, Some(Argon2HashService::HASH_OUTPUT_LENGTH))
+                .expect("Expect valid Argon2 params"),
+        );
+        let argon2 = Argon2HashService::new();
+
+        let hash = argon2
+            .hash_password("12345678")
+            .expect("Expect to hash the password");
+        assert!(!argon2.needs_rehash(&hash));
+
+        let outdated_hash = outdated
+            .hash_password("12345678")
+            .expect("Expect to hash the password");
+        assert!(argon2.needs_rehash(&outdated_hash));
+        assert_eq!(argon2.verify_password("12345678", &outdated_hash), Ok(()));
+    }
+}
+
+/// Bcrypt hasher, to verify the passwords migrated from a legacy store.
+pub struct BcryptHashService {
+    cost: u32,
+}
+
+impl BcryptHashService {
+    pub const ALGORITHM: PasswordHashAlgorithm = PasswordHashAlgorithm::Bcrypt;
+    pub const DEFAULT_COST: u32 = bcrypt::DEFAULT_COST;
+    pub const MIN_COST: u32 = 4;
+    pub const MAX_COST: u32 = 31;
+    pub const SALT_LENGTH: usize = 16;
+    /// Bcrypt encodes only 23 of the 24 output bytes.
+    pub const HASH_OUTPUT_LENGTH: usize = 23;
+    /// Bcrypt ignores the password bytes after the 72th, so longer passwords are not hashed.
+    pub const MAX_PASSWORD_LENGTH: usize = 72;
+
+    pub fn new() -> Self {
+        Self::with_cost(Self::DEFAULT_COST)
+    }
+
+    /// # Panic
+    ///
+    /// Panics if `cost` is out of the [`Self::MIN_COST`] and [`Self::MAX_COST`] range
+    pub fn with_cost(cost: u32) -> Self {
+        assert!(
+            (Self::MIN_COST..=Self::MAX_COST).contains(&cost),
+            "Expect a bcrypt cost between {} and {}",
+            Self::MIN_COST,
+            Self::MAX_COST
+        );
+        Self { cost }
+    }
+
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    fn compute(
+        cost: u32,
+        salt: [u8; Self::SALT_LENGTH],
+        pwd: &str,
+    ) -> Result<OutputHash, PasswordHashError> {
+        // Null terminated, as the reference implementation.
+        let mut input: Vec<u8> = pwd.bytes().chain(std::iter::once(0)).collect();
+        input.truncate(Self::MAX_PASSWORD_LENGTH);
+
+        let output = bcrypt::bcrypt(cost, salt, &input);
+        Ok(OutputHash::new(&output[..Self::HASH_OUTPUT_LENGTH])?)
+    }
+}
+
+impl Default for BcryptHashService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PasswordHashService for BcryptHashService {
+    fn hash_password(&self, pwd: &str) -> Result<PasswordHash, PasswordHashError> {
+        if pwd.len() > Self::MAX_PASSWORD_LENGTH {
+            return Err(PasswordHashError::PasswordTooLong(
+                Self::MAX_PASSWORD_LENGTH,
+            ));
+        }
+
+        let mut salt = [0; Self::SALT_LENGTH];
+        rand_core::RngCore::fill_bytes(&mut rand_core::OsRng, &mut salt);
+
+        let hash = Self::compute(self.cost, salt, pwd)?;
+
+        Ok(PasswordHash::new_bcrypt(
+            self.cost,
+            Some(SaltString::b64_encode(&salt)?),
+            Some(hash),
+        ))
+    }
+
+    fn verify_password(&self, pwd: &str, hash: &PasswordHash) -> Result<(), PasswordHashError> {
+        if *hash.algorithm() != Self::ALGORITHM {
+            return Err(PasswordHashError::UnsupportedAlgorithm);
+        }
+
+        if let (Some(salt), Some(expected_output)) = (hash.salt(), hash.hash()) {
+            let cost = hash
+                .params()
+                .get_decimal("cost")
+                .or_else(|| hash.params().get_decimal("c"))
+                .filter(|cost| (Self::MIN_COST..=Self::MAX_COST).contains(cost))
+                .ok_or(PasswordHashError::InvalidPasswordHash)?;
+
+            let mut buf = [0; Self::SALT_LENGTH];
+            let salt: [u8; Self::SALT_LENGTH] = salt
+                .b64_decode(&mut buf)?
+                .try_into()
+                .map_err(|_| PasswordHashError::InvalidPasswordHash)?;
+
+            if *expected_output == Self::compute(cost, salt, pwd)? {
+                return Ok(());
+            }
+        }
+
+        Err(PasswordHashError::InvalidPassword)
+    }
+
+    fn algorithm(&self) -> PasswordHashAlgorithm {
+        Self::ALGORITHM
+    }
+
+    fn hash_params(&self) -> PasswordParams {
+        PasswordHash::new_bcrypt(self.cost, None, None)
+            .params()
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod bcrypt_hash_service_test {
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{Argon2HashService, BcryptHashService};
+    use crate::domain::{
+        datatype::security::{PasswordHash, PasswordHashError},
+        service::PasswordHashService,
+    };
+
+    #[test]
+    fn hash_serialize_and_verify_password() {
+        let bcrypt = BcryptHashService::with_cost(BcryptHashService::MIN_COST);
+
+        let pwds = [
+            "super_secret",
+            "12345678",
+            "onw*(*#028]][2389nfwCSOEN",
+            "no",
+        ];
+
+        for pwd in pwds {
+            let hash = bcrypt
+                .hash_password(pwd)
+                .expect("Expect to hash the password");
+
+            let deserialized = PasswordHash::from_str(&hash.to_string())
+                .expect("Expect to deserialize the password");
+            assert_eq!(deserialized, hash);
+
+            assert_eq!(bcrypt.verify_password(pwd, &deserialized), Ok(()));
+        }
+    }
+
+    #[test]
+    fn verify_with_the_hash_cost() {
+        let hash = BcryptHashService::with_cost(5)
+            .hash_password("12345678")
+            .expect("Expect to hash the password");
+
+        let bcrypt = BcryptHashService::with_cost(BcryptHashService::MIN_COST);
+        assert_eq!(bcrypt.verify_password("12345678", &hash), Ok(()));
+    }
+
+    #[test]
+    fn reject_password_longer_than_72_bytes() {
+        let bcrypt = BcryptHashService::with_cost(BcryptHashService::MIN_COST);
+
+        let pwd = "a".repeat(BcryptHashService::MAX_PASSWORD_LENGTH);
+        let hash = bcrypt
+            .hash_password(&pwd)
+            .expect("Expect to hash a 72 bytes password");
+        assert_eq!(bcrypt.verify_password(&pwd, &hash), Ok(()));
+
+        let pwd = "a".repeat(100);
+        assert_eq!(
+            bcrypt.hash_password(&pwd),
+            Err(PasswordHashError::PasswordTooLong(
+                BcryptHashService::MAX_PASSWORD_LENGTH
+            ))
+        );
+
+        let argon2 = Argon2HashService::new();
+        let hash = argon2
+            .hash_password(&pwd)
+            .expect("Expect argon2 to hash a 100 bytes password");
+        assert_eq!(argon2.verify_password(&pwd, &hash), Ok(()));
+    }
+
+    #[test]
+    fn reject_wrong_password() {
+        let bcrypt = BcryptHashService::with_cost(BcryptHashService::MIN_COST);
+
+        let hash = bcrypt
+            .hash_password("super_secret")
+            .expect("Expect to hash the password");
+
+        assert_eq!(
+            bcrypt.verify_password("super_secreT", &hash),
+            Err(PasswordHashError::InvalidPassword)
+        );
+    }
+}
+
+/// Password hasher of every supported algorithm.
+///
+/// New passwords are hashed with argon2, and the stored hashes verified by their own algorithm,
+/// so the accounts migrated with a bcrypt hash are still authenticated.
+pub struct CompositeHashService {
+    argon2: Argon2HashService,
+    bcrypt: BcryptHashService,
+    /// Hash of no account, verified in place of a unknown account.
+    dummy_hash: PasswordHash,
+}
+
+impl CompositeHashService {
+    pub fn new(argon2: Argon2HashService) -> Self {
+        Self::with_bcrypt(argon2, BcryptHashService::new())
+    }
+
+    pub fn with_bcrypt(argon2: Argon2HashService, bcrypt: BcryptHashService) -> Self {
+        let dummy_hash = argon2
+            .hash_password("dummy_password")
+            .expect("Expect to hash the dummy password");
+
+        Self {
+            argon2,
+            bcrypt,
+            dummy_hash,
+        }
+    }
+}
+
+impl PasswordHashService for CompositeHashService {
+    fn hash_password(&self, pwd: &str) -> Result<PasswordHash, PasswordHashError> {
+        self.argon2.hash_password(pwd)
+    }
+
+    fn verify_password(&self, pwd: &str, hash: &PasswordHash) -> Result<(), PasswordHashError> {
+        match hash.algorithm() {
+            PasswordHashAlgorithm::Argon2d
+            | PasswordHashAlgorithm::Argon2i
+            | PasswordHashAlgorithm::Argon2id => self.argon2.verify_password(pwd, hash),
+            PasswordHashAlgorithm::Bcrypt => self.bcrypt.verify_password(pwd, hash),
+        }
+    }
+
+    fn algorithm(&self) -> PasswordHashAlgorithm {
+        self.argon2.algorithm()
+    }
+
+    fn hash_params(&self) -> PasswordParams {
+        self.argon2.hash_params()
+    }
+
+    fn verify_dummy_password(&self, pwd: &str) {
+        let _ = self.verify_password(pwd, &self.dummy_hash);
+    }
+}
+
+#[cfg(test)]
+mod composite_hash_service_test {
+    use pretty_assertions::assert_eq;
+
+    use super::{Argon2HashService, BcryptHashService, CompositeHashService};
+    use crate::domain::{
+        datatype::security::{PasswordHashAlgorithm, PasswordHashError},
+        service::PasswordHashService,
+    };
+
+    #[test]
+    fn hash_with_argon2() {
+        let service = CompositeHashService::new(Argon2HashService::new());
+
+        let hash = service
+            .hash_password("super_secret")
+            .expect("Expect to hash the password");
+
+        assert_eq!(hash.algorithm(), &PasswordHashAlgorithm::Argon2id);
+    }
+
+    #[test]
+    fn rehash_bcrypt_hashes() {
+        let service = CompositeHashService::new(Argon2HashService::new());
+
+        let hash = BcryptHashService::with_cost(BcryptHashService::MIN_COST)
+            .hash_password("super_secret")
+            .expect("Expect to hash the password");
+
+        assert!(service.needs_rehash(&hash));
+    }
+
+    #[test]
+    fn verify_dummy_hash_with_configured_params() {
+        let service = CompositeHashService::new(Argon2HashService::new());
+
+        assert!(!service.needs_rehash(&service.dummy_hash));
+        assert_eq!(
+            service.verify_password("super_secret", &service.dummy_hash),
+            Err(PasswordHashError::InvalidPassword)
+        );
+    }
+
+    #[test]
+    fn verify_argon2_and_bcrypt_hashes() {
+        let argon2 = Argon2HashService::new();
+        let bcrypt = BcryptHashService::with_cost(BcryptHashService::MIN_COST);
+
+        let hashes = [
+            (
+                "super_secret",
+                argon2
+                    .hash_password("super_secret")
+                    .expect("Expect to hash the password"),
+            ),
+            (
+                "12345678",
+                bcrypt
+                    .hash_password("12345678")
+                    .expect("Expect to hash the password"),
+            ),
+            (
+                "onw*(*#028]][2389nfwCSOEN",
+                bcrypt
+                    .hash_password("onw*(*#028]][2389nfwCSOEN")
+                    .expect("Expect to hash the password"),
+            ),
+            (
+                "no",
+                argon2
+                    .hash_password("no")
+                    .expect("Expect to hash the password"),
+            ),
+        ];
+
+        let service = CompositeHashService::with_bcrypt(argon2, bcrypt);
+        for (pwd, hash) in &hashes {
+            assert_eq!(service.verify_password(pwd, hash), Ok(()));
+            assert_eq!(
+                service.verify_password("wrong_password", hash),
+                Err(PasswordHashError::InvalidPassword)
+            );
+        }
+    }
 }
 
 pub struct JWTEncryptionService {
+    issuer: TokenIssuer,
     header: Header,
-    encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    keys: RwLock<TokenKeys>,
     validation: Validation,
+    /// Public keys, published for the services verifying the tokens.
+    jwks: Vec<Jwk>,
+}
+
+/// Token signing keys, identified by the `kid` header.
+struct TokenKeys {
+    /// Id of the key signing the new tokens.
+    active: String,
+    encoding: EncodingKey,
+    decoding: HashMap<String, DecodingKey>,
+    /// Id of the key replaced in the last rotation, only used to verify tokens.
+    previous: Option<String>,
+}
+
+impl TokenKeys {
+    fn new(
+        algorithm: jsonwebtoken::Algorithm,
+        encoding: EncodingKey,
+        decoding: DecodingKey,
+    ) -> Self {
+        let active = JWTEncryptionService::key_id(&encoding, algorithm);
+        Self {
+            decoding: HashMap::from([(active.clone(), decoding)]),
+            active,
+            encoding,
+            previous: None,
+        }
+    }
+}
+
+/// Public key of a JSON Web Key Set, as RFC 7517.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Jwk {
+    kty: &'static str,
+    #[serde(rename = "use")]
+    usage: &'static str,
+    alg: &'static str,
+    kid: String,
+    /// Base64url encoded RSA modulus.
+    n: String,
+    /// Base64url encoded RSA public exponent.
+    e: String,
+}
+
+impl Jwk {
+    /// Key of a RSA public key PEM, either a `PUBLIC KEY` or a `RSA PUBLIC KEY`.
+    fn rsa(kid: String, pem: &[u8]) -> Option<Self> {
+        let pem = std::str::from_utf8(pem).ok()?;
+        let b64: String = pem
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        let der = base64::decode(b64.trim()).ok()?;
+
+        let (key, _) = der::element(&der, der::SEQUENCE)?;
+        let key = match der::element(key, der::SEQUENCE) {
+            // SubjectPublicKeyInfo, with the algorithm before the key bit string
+            Some((_, rest)) => {
+                let (bits, _) = der::element(rest, der::BIT_STRING)?;
+                let (key, _) = der::element(bits.strip_prefix(&[0])?, der::SEQUENCE)?;
+                key
+            }
+            None => key,
+        };
+        let (n, rest) = der::element(key, der::INTEGER)?;
+        let (e, _) = der::element(rest, der::INTEGER)?;
+
+        let encode = |int: &[u8]| {
+            let unsigned = &int[int.iter().take_while(|byte| **byte == 0).count()..];
+            base64::encode_config(unsigned, base64::URL_SAFE_NO_PAD)
+        };
+        Some(Self {
+            kty: "RSA",
+            usage: "sig",
+            alg: "RS256",
+            kid,
+            n: encode(n),
+            e: encode(e),
+        })
+    }
+}
+
+/// Token verification keys, served at `/.well-known/jwks.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+/// The DER subset of a RSA public key.
+mod der {
+    pub const INTEGER: u8 = 0x02;
+    pub const BIT_STRING: u8 = 0x03;
+    pub const SEQUENCE: u8 = 0x30;
+
+    /// Content of the element of `tag` in front of `input`, and the input after it.
+    pub fn element(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+        let (&found, rest) = input.split_first()?;
+        if found != tag {
+            return None;
+        }
+
+        let (&len, mut rest) = rest.split_first()?;
+        let len = if len < 0x80 {
+            len as usize
+        } else {
+            // long form, the length in the next `len & 0x7f` bytes
+            let octets = (len & 0x7f) as usize;
+            if octets == 0 || octets > 4 || rest.len() < octets {
+                return None;
+            }
+            let (len, tail) = rest.split_at(octets);
+            rest = tail;
+            len.iter().fold(0, |len, byte| len << 8 | *byte as usize)
+        };
+
+        if rest.len() < len {
+            return None;
+        }
+        Some(rest.split_at(len))
+    }
 }
 
 impl JWTEncryptionService {
-    pub fn new(secret: &[u8]) -> Self {
-        let mut validation = Validation::new(jsonwebtoken::Algorithm::HS256);
+    pub const ALGORITHM: jsonwebtoken::Algorithm = jsonwebtoken::Algorithm::HS256;
+    /// Length of the key ids.
+    pub const KEY_ID_LENGTH: usize = 16;
+
+    pub fn new(secret: &[u8], issuer: TokenIssuer) -> Self {
+        Self::with_keys(
+            Self::ALGORITHM,
+            EncodingKey::from_secret(secret),
+            DecodingKey::from_secret(secret),
+            issuer,
+        )
+    }
+
+    /// Sign with a RSA private key, so the services verifying the tokens only hold the public key.
+    pub fn new_rs256(
+        encoding_pem: &[u8],
+        decoding_pem: &[u8],
+        issuer: TokenIssuer,
+    ) -> Result<Self, TokenEncryptionError> {
+        let service = Self::with_keys(
+            jsonwebtoken::Algorithm::RS256,
+            EncodingKey::from_rsa_pem(encoding_pem)?,
+            DecodingKey::from_rsa_pem(decoding_pem)?,
+            issuer,
+        );
+        let jwk = Jwk::rsa(service.active_key_id(), decoding_pem)
+            .ok_or(TokenEncryptionError::InvalidAlgorithm)?;
+        Ok(Self {
+            jwks: vec![jwk],
+            ..service
+        })
+    }
+
+    fn with_keys(
+        algorithm: jsonwebtoken::Algorithm,
+        encoding: EncodingKey,
+        decoding: DecodingKey,
+        issuer: TokenIssuer,
+    ) -> Self {
+        let mut validation = Validation::new(algorithm);
         validation.set_required_spec_claims(&["exp", "iss", "sub"]);
-        validation.set_issuer(&[TokenIssuer::as_str()]);
+        validation.set_issuer(&[issuer.as_str()]);
         validation.leeway = 60;
         validation.validate_exp = true;
         validation.validate_nbf = false;
 
         Self {
-            encoding_key: EncodingKey::from_secret(secret),
-            decoding_key: DecodingKey::from_secret(secret),
-            header: Header::new(jsonwebtoken::Algorithm::HS256),
+            keys: RwLock::new(TokenKeys::new(algorithm, encoding, decoding)),
+            header: Header::new(algorithm),
             validation,
+            issuer,
+            jwks: Vec::new(),
         }
     }
 
+    /// Service of the configured keys, the RSA key pair when its PEM files are set.
     pub fn from_config() -> Self {
-        Self::new(crate::config::env_var::get().token_key.as_ref())
+        let env = crate::config::env_var::get();
+        match (&env.token_private_key_file, &env.token_public_key_file) {
+            (Some(private), Some(public)) => Self::new_rs256(
+                &std::fs::read(private).expect("Expect to read the TOKEN_PRIVATE_KEY_FILE"),
+                &std::fs::read(public).expect("Expect to read the TOKEN_PUBLIC_KEY_FILE"),
+                env.token_issuer.clone(),
+            )
+            .expect("Invalid token RSA key pair"),
+            _ => Self::new(env.token_key.as_ref(), env.token_issuer.clone()),
+        }
+    }
+
+    /// Algorithm signing the tokens.
+    pub fn algorithm(&self) -> jsonwebtoken::Algorithm {
+        self.header.alg
+    }
+
+    /// Public keys verifying the tokens.
+    ///
+    /// Empty for the HS256 service, the secret that verifies also signs.
+    pub fn jwk_set(&self) -> JwkSet {
+        JwkSet {
+            keys: self.jwks.clone(),
+        }
+    }
+
+    /// Id of a signing key, the same in every instance holding the key.
+    ///
+    /// A signature of a constant message, so the id does not disclose the key.
+    fn key_id(encoding: &EncodingKey, algorithm: jsonwebtoken::Algorithm) -> String {
+        let mut kid = jsonwebtoken::crypto::sign(b"key_id", encoding, algorithm)
+            .expect("Expect to sign with the token key");
+        kid.truncate(Self::KEY_ID_LENGTH);
+        kid
+    }
+
+    /// Id of the key signing the new tokens.
+    pub fn active_key_id(&self) -> String {
+        self.keys
+            .read()
+            .expect("Expect the token keys lock not to be poisoned")
+            .active
+            .clone()
+    }
+
+    /// Sign the new tokens with `secret`.
+    ///
+    /// Tokens signed with the replaced key are still verified until the next rotation,
+    /// so the ones already issued are not invalidated.
+    ///
+    /// Fails with [`TokenEncryptionError::InvalidAlgorithm`] on a RS256 service,
+    /// a secret does not replace a RSA key pair.
+    pub fn rotate_key(&self, secret: &[u8]) -> Result<(), TokenEncryptionError> {
+        if self.algorithm() != Self::ALGORITHM {
+            return Err(TokenEncryptionError::InvalidAlgorithm);
+        }
+
+        let encoding = EncodingKey::from_secret(secret);
+        let kid = Self::key_id(&encoding, self.header.alg);
+
+        let mut keys = self
+            .keys
+            .write()
+            .expect("Expect the token keys lock not to be poisoned");
+        if keys.active == kid {
+            return Ok(());
+        }
+
+        if let Some(expired) = keys.previous.take() {
+            keys.decoding.remove(&expired);
+        }
+        keys.decoding
+            .insert(kid.clone(), DecodingKey::from_secret(secret));
+        keys.encoding = encoding;
+        keys.previous = Some(std::mem::replace(&mut keys.active, kid));
+        Ok(())
+    }
+
+    /// Decoding key of the token `kid`.
+    ///
+    /// Tokens issued before the `kid` header are verified with the active key.
+    fn decoding_key(&self, token: &str) -> Result<DecodingKey, TokenEncryptionError> {
+        let header = jsonwebtoken::decode_header(token)?;
+        let keys = self
+            .keys
+            .read()
+            .expect("Expect the token keys lock not to be poisoned");
+        let kid = header.kid.as_ref().unwrap_or(&keys.active);
+        keys.decoding
+            .get(kid)
+            .cloned()
+            .ok_or(TokenEncryptionError::UnknownKey)
+    }
+
+    fn decode<T>(
+        &self,
+        token: &str,
+        key: &DecodingKey,
+        validation: &Validation,
+    ) -> jsonwebtoken::errors::Result<TokenPayload<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let claims: TokenPayload<T> = jsonwebtoken::decode(token, key, validation)?.claims;
+
+        // `iat` is not validated by jsonwebtoken, a issuer clock ahead is tolerated up to the leeway
+        if claims.issued_at() > jsonwebtoken::get_current_timestamp() + validation.leeway {
+            return Err(ErrorKind::ImmatureSignature.into());
+        }
+        Ok(claims)
+    }
+
+    /// Verify the token by the key of its `kid` header against `validation`.
+    fn decode_with<T>(
+        &self,
+        token: &str,
+        validation: &Validation,
+    ) -> Result<TokenPayload<T>, TokenEncryptionError>
+    where
+        T: DeserializeOwned,
+    {
+        let key = self.decoding_key(token)?;
+        match self.decode::<T>(token, &key, validation) {
+            // the data is deserialized after the signature and claims validation
+            Err(err) if matches!(err.kind(), ErrorKind::Json(_)) => {
+                if self.decode::<IgnoredAny>(token, &key, validation).is_ok() {
+                    return Err(TokenEncryptionError::UnexpectedPayload);
+                }
+                Err(err.into())
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Verify a batch of tokens, sharing the decoding key and validation.
+    ///
+    /// Results are in the same order of the tokens.
+    pub fn verify_many<T>(
+        &self,
+        tokens: &[&str],
+    ) -> Vec<Result<TokenPayload<T>, TokenEncryptionError>>
+    where
+        T: DeserializeOwned,
+    {
+        tokens
+            .iter()
+            .map(|token| self.verify_token(token))
+            .collect()
     }
 }
 
 impl TokenEncryptionService for JWTEncryptionService {
+    fn issuer(&self) -> &TokenIssuer {
+        &self.issuer
+    }
+
     fn issue_token<T>(&self, payload: &TokenPayload<T>) -> Result<String, TokenEncryptionError>
     where
         T: Serialize,
     {
-        let token = jsonwebtoken::encode(&self.header, payload, &self.encoding_key)?;
+        let keys = self
+            .keys
+            .read()
+            .expect("Expect the token keys lock not to be poisoned");
+        let header = Header {
+            kid: Some(keys.active.clone()),
+            ..self.header.clone()
+        };
+        let token = jsonwebtoken::encode(&header, payload, &keys.encoding)?;
         Ok(token)
     }
 
@@ -146,22 +953,35 @@ impl TokenEncryptionService for JWTEncryptionService {
     where
         T: DeserializeOwned,
     {
-        let token_data = jsonwebtoken::decode(token, &self.decoding_key, &self.validation)?;
-        Ok(token_data.claims)
+        self.decode_with(token, &self.validation)
+    }
+
+    fn inspect_token<T>(&self, token: &str) -> Result<TokenPayload<T>, TokenEncryptionError>
+    where
+        T: DeserializeOwned,
+    {
+        let mut validation = self.validation.clone();
+        validation.validate_exp = false;
+        self.decode_with(token, &validation)
     }
 }
 
 #[cfg(test)]
 mod jwt_encryption_service_test {
-    use std::{cmp, fmt, time::Duration};
+    use std::{cmp, fmt, str::FromStr, time::Duration};
 
     use serde::{de::DeserializeOwned, Deserialize, Serialize};
     use uuid::Uuid;
 
+    use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+
     use super::JWTEncryptionService;
-    use crate::domain::{
-        datatype::security::{TokenPayload, TokenSubject},
-        service::TokenEncryptionService,
+    use crate::{
+        domain::{
+            datatype::security::{TokenEncryptionError, TokenIssuer, TokenPayload, TokenSubject},
+            service::TokenEncryptionService,
+        },
+        error::security::AuthenticationError,
     };
 
     #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -191,17 +1011,23 @@ mod jwt_encryption_service_test {
             assert_eq!(parsed_payload, payload);
         }
 
-        let jwt = JWTEncryptionService::new("my_secret".as_bytes());
+        let jwt = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
 
         issue_and_verify(
             &jwt,
-            TokenPayload::new(Duration::from_secs(10), TokenSubject::Public, ()),
+            TokenPayload::new(
+                Duration::from_secs(10),
+                TokenIssuer::default(),
+                TokenSubject::Public,
+                (),
+            ),
         );
 
         issue_and_verify(
             &jwt,
             TokenPayload::new(
                 Duration::from_secs(10),
+                TokenIssuer::default(),
                 TokenSubject::User(Uuid::new_v4()),
                 RolesPayload {
                     roles: vec!["admin".into()],
@@ -213,9 +1039,425 @@ mod jwt_encryption_service_test {
             &jwt,
             TokenPayload::new(
                 Duration::from_secs(10),
+                TokenIssuer::default(),
                 TokenSubject::Public,
                 RolesPayload { roles: vec![] },
             ),
         );
     }
+
+    #[test]
+    fn publish_the_rs256_public_key() {
+        let jwt = JWTEncryptionService::new_rs256(
+            include_bytes!("fixture/rs256_private.pem"),
+            include_bytes!("fixture/rs256_public.pem"),
+            TokenIssuer::default(),
+        )
+        .expect("Expect valid RSA keys");
+
+        assert_eq!(jwt.algorithm(), jsonwebtoken::Algorithm::RS256);
+
+        let jwks = jwt.jwk_set();
+        assert_eq!(jwks.keys.len(), 1);
+        let jwk = &jwks.keys[0];
+        assert_eq!((jwk.kty, jwk.usage, jwk.alg), ("RSA", "sig", "RS256"));
+        assert_eq!(jwk.kid, jwt.active_key_id());
+        assert_eq!(jwk.e, "AQAB");
+        assert!(jwk.n.starts_with("qzpRbZ85_9QESWza8fiJ"));
+
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            TokenIssuer::default(),
+            TokenSubject::Public,
+            (),
+        );
+        let token = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+
+        // as a service holding only the published key would verify
+        let key =
+            DecodingKey::from_rsa_components(&jwk.n, &jwk.e).expect("Expect a valid published key");
+        let validation = Validation::new(jsonwebtoken::Algorithm::RS256);
+        jsonwebtoken::decode::<TokenPayload<()>>(&token, &key, &validation)
+            .expect("Expect to verify the token with the published key");
+    }
+
+    #[test]
+    fn not_publish_the_hs256_secret() {
+        let jwt = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+        assert_eq!(jwt.algorithm(), jsonwebtoken::Algorithm::HS256);
+        assert_eq!(jwt.jwk_set().keys, vec![]);
+    }
+
+    #[test]
+    fn not_rotate_the_rs256_key_pair() {
+        let jwt = JWTEncryptionService::new_rs256(
+            include_bytes!("fixture/rs256_private.pem"),
+            include_bytes!("fixture/rs256_public.pem"),
+            TokenIssuer::default(),
+        )
+        .expect("Expect valid RSA keys");
+        let kid = jwt.active_key_id();
+
+        assert!(matches!(
+            jwt.rotate_key("my_secret".as_bytes()),
+            Err(TokenEncryptionError::InvalidAlgorithm)
+        ));
+        assert_eq!(jwt.active_key_id(), kid);
+
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            TokenIssuer::default(),
+            TokenSubject::Public,
+            (),
+        );
+        let token = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+        assert!(jwt.verify_token::<()>(&token).is_ok());
+    }
+
+    #[test]
+    fn issue_and_verify_rs256_token() {
+        let jwt = JWTEncryptionService::new_rs256(
+            include_bytes!("fixture/rs256_private.pem"),
+            include_bytes!("fixture/rs256_public.pem"),
+            TokenIssuer::default(),
+        )
+        .expect("Expect valid RSA keys");
+
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            TokenIssuer::default(),
+            TokenSubject::User(Uuid::new_v4()),
+            RolesPayload {
+                roles: vec!["admin".into()],
+            },
+        );
+
+        let token = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+        let header = jsonwebtoken::decode_header(&token).expect("Expect a valid token header");
+        assert_eq!(header.alg, jsonwebtoken::Algorithm::RS256);
+
+        let parsed_payload: TokenPayload<RolesPayload> = jwt
+            .verify_token(&token)
+            .expect("Expect to verify the token");
+        assert_eq!(parsed_payload, payload);
+
+        let hs256 = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+        assert!(hs256.verify_token::<RolesPayload>(&token).is_err());
+    }
+
+    #[test]
+    fn verify_custom_issuer() {
+        let issuer =
+            TokenIssuer::from_str("staging.conduit.blog.app").expect("Expect a valid token issuer");
+        let staging = JWTEncryptionService::new("my_secret".as_bytes(), issuer.clone());
+        let production = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            staging.issuer().clone(),
+            TokenSubject::Public,
+            (),
+        );
+        let token = staging
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+
+        let verified: TokenPayload<()> = staging
+            .verify_token(&token)
+            .expect("Expect to verify the token");
+        assert_eq!(*verified.issuer(), issuer);
+
+        let result = production.verify_token::<()>(&token);
+        assert!(matches!(result, Err(TokenEncryptionError::InvalidPayload)));
+    }
+
+    #[test]
+    fn reject_payload_of_other_purpose() {
+        let jwt = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            TokenIssuer::default(),
+            TokenSubject::Public,
+            (),
+        );
+        let token = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+
+        assert!(matches!(
+            jwt.verify_token::<RolesPayload>(&token),
+            Err(TokenEncryptionError::UnexpectedPayload)
+        ));
+        assert!(matches!(
+            jwt.verify_token::<RolesPayload>("not.a.token"),
+            Err(TokenEncryptionError::InvalidToken)
+        ));
+        assert!(matches!(
+            AuthenticationError::from(TokenEncryptionError::UnexpectedPayload),
+            AuthenticationError::InvalidPayload
+        ));
+    }
+
+    #[test]
+    fn tolerate_issuer_clock_skew_within_leeway() {
+        #[derive(Serialize)]
+        struct SkewedClaims {
+            exp: u64,
+            iat: u64,
+            iss: &'static str,
+            sub: &'static str,
+            data: (),
+        }
+
+        let jwt = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+        let issue_ahead = |skew: u64| {
+            let iat = jsonwebtoken::get_current_timestamp() + skew;
+            jsonwebtoken::encode(
+                &Header::new(JWTEncryptionService::ALGORITHM),
+                &SkewedClaims {
+                    exp: iat + 600,
+                    iat,
+                    iss: TokenIssuer::DEFAULT,
+                    sub: "public",
+                    data: (),
+                },
+                &EncodingKey::from_secret("my_secret".as_bytes()),
+            )
+            .expect("Expect to encode the token")
+        };
+
+        assert!(jwt.verify_token::<()>(&issue_ahead(30)).is_ok());
+        assert!(matches!(
+            jwt.verify_token::<()>(&issue_ahead(120)),
+            Err(TokenEncryptionError::InvalidPayload)
+        ));
+    }
+
+    #[test]
+    fn inspect_token_expired_beyond_leeway() {
+        #[derive(Serialize)]
+        struct ExpiredClaims {
+            exp: u64,
+            iat: u64,
+            iss: &'static str,
+            sub: &'static str,
+            data: (),
+        }
+
+        let jwt = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+        let issue_expired = |secret: &str| {
+            let exp = jsonwebtoken::get_current_timestamp() - 3600;
+            jsonwebtoken::encode(
+                &Header::new(JWTEncryptionService::ALGORITHM),
+                &ExpiredClaims {
+                    exp,
+                    iat: exp - 600,
+                    iss: TokenIssuer::DEFAULT,
+                    sub: "public",
+                    data: (),
+                },
+                &EncodingKey::from_secret(secret.as_bytes()),
+            )
+            .expect("Expect to encode the token")
+        };
+
+        let token = issue_expired("my_secret");
+        assert!(jwt.verify_token::<()>(&token).is_err());
+
+        let payload = jwt
+            .inspect_token::<()>(&token)
+            .expect("Expect to inspect the expired token");
+        assert!(payload.expired());
+
+        let forged = issue_expired("other_secret");
+        assert!(jwt.inspect_token::<()>(&forged).is_err());
+    }
+
+    #[test]
+    fn verify_tokens_after_key_rotation() {
+        let jwt = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            TokenIssuer::default(),
+            TokenSubject::Public,
+            (),
+        );
+
+        let before = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+        jwt.rotate_key("new_secret".as_bytes())
+            .expect("Expect to rotate the HS256 key");
+        let after = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+
+        assert!(jwt.verify_token::<()>(&before).is_ok());
+        assert!(jwt.verify_token::<()>(&after).is_ok());
+
+        jwt.rotate_key("newer_secret".as_bytes())
+            .expect("Expect to rotate the HS256 key");
+
+        assert!(matches!(
+            jwt.verify_token::<()>(&before),
+            Err(TokenEncryptionError::UnknownKey)
+        ));
+        assert!(jwt.verify_token::<()>(&after).is_ok());
+    }
+
+    #[test]
+    fn stamp_the_signing_key_id() {
+        let jwt = JWTEncryptionService::new("key_a".as_bytes(), TokenIssuer::default());
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            TokenIssuer::default(),
+            TokenSubject::Public,
+            (),
+        );
+
+        let key_a = jwt.active_key_id();
+        let token_a = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+
+        jwt.rotate_key("key_b".as_bytes())
+            .expect("Expect to rotate the HS256 key");
+        let key_b = jwt.active_key_id();
+        let token_b = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+
+        assert_ne!(key_a, key_b);
+        let kid = |token: &str| {
+            jsonwebtoken::decode_header(token)
+                .expect("Expect a valid token header")
+                .kid
+        };
+        assert_eq!(kid(&token_a), Some(key_a));
+        assert_eq!(kid(&token_b), Some(key_b));
+
+        assert!(jwt.verify_token::<()>(&token_a).is_ok());
+        assert!(jwt.verify_token::<()>(&token_b).is_ok());
+
+        // other instance holding the same key
+        let other = JWTEncryptionService::new("key_b".as_bytes(), TokenIssuer::default());
+        assert_eq!(other.active_key_id(), jwt.active_key_id());
+        assert!(other.verify_token::<()>(&token_b).is_ok());
+    }
+
+    #[test]
+    fn reject_unknown_key_id() {
+        let jwt = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            TokenIssuer::default(),
+            TokenSubject::Public,
+            (),
+        );
+
+        let header = Header {
+            kid: Some("unknown".into()),
+            ..Header::new(JWTEncryptionService::ALGORITHM)
+        };
+        let token = jsonwebtoken::encode(
+            &header,
+            &payload,
+            &EncodingKey::from_secret("my_secret".as_bytes()),
+        )
+        .expect("Expect to issue the token");
+
+        assert!(matches!(
+            jwt.verify_token::<()>(&token),
+            Err(TokenEncryptionError::UnknownKey)
+        ));
+    }
+
+    #[test]
+    fn verify_many_tokens() {
+        #[derive(Serialize)]
+        struct ExpiredClaims {
+            exp: u64,
+            iat: u64,
+            iss: &'static str,
+            sub: &'static str,
+            data: (),
+        }
+
+        let jwt = JWTEncryptionService::new("my_secret".as_bytes(), TokenIssuer::default());
+        let other_jwt =
+            JWTEncryptionService::new("other_secret".as_bytes(), TokenIssuer::default());
+
+        let payload = TokenPayload::new(
+            Duration::from_secs(10),
+            TokenIssuer::default(),
+            TokenSubject::Public,
+            (),
+        );
+        let valid = jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+        let tampered = other_jwt
+            .issue_token(&payload)
+            .expect("Expect to issue the token");
+        let expired = jsonwebtoken::encode(
+            &Header::new(JWTEncryptionService::ALGORITHM),
+            &ExpiredClaims {
+                exp: 1,
+                iat: 0,
+                iss: TokenIssuer::DEFAULT,
+                sub: "public",
+                data: (),
+            },
+            &EncodingKey::from_secret("my_secret".as_bytes()),
+        )
+        .expect("Expect to encode the token");
+
+        let results = jwt.verify_many::<()>(&[&valid, &expired, &tampered, "not.a.token"]);
+
+        assert_eq!(results.len(), 4);
+        assert!(matches!(&results[0], Ok(verified) if *verified == payload));
+        assert!(matches!(
+            results[1],
+            Err(TokenEncryptionError::TokenExpired)
+        ));
+        assert!(matches!(
+            results[2],
+            Err(TokenEncryptionError::InvalidPayload)
+        ));
+        assert!(matches!(
+            results[3],
+            Err(TokenEncryptionError::InvalidToken)
+        ));
+    }
+}
+
+/// Audit sink persisting the events in the `iam.audit_log` table.
+pub struct DatabaseAuditSink {
+    pool: PgPool,
+}
+
+impl DatabaseAuditSink {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditSink for DatabaseAuditSink {
+    async fn record(&self, event: AuditEvent) {
+        if let Err(err) = repository::insert_audit_event(&self.pool, &event).await {
+            tracing::error!(
+                target = "audit",
+                action = %event.action,
+                actor = ?event.actor,
+                cause = %err,
+            );
+        }
+    }
 }
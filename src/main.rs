@@ -1,6 +1,6 @@
 #![warn(clippy::unwrap_used)]
 
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use salvo::{listener::TcpListener, Server};
 use tokio::signal::ctrl_c;
@@ -8,7 +8,7 @@ use tokio::signal::ctrl_c;
 use config::env_var;
 use infra::{
     database, router,
-    service::{Argon2HashService, JWTEncryptionService},
+    service::{Argon2HashService, CompositeHashService, DatabaseAuditSink, JWTEncryptionService},
 };
 
 mod app;
@@ -30,17 +30,94 @@ async fn handle_shutdown() {
     }
 }
 
+/// Rotate the token key with the content of `path` on every SIGHUP.
+#[cfg(unix)]
+async fn handle_token_key_reload(token_service: Arc<JWTEncryptionService>, path: String) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            tracing::error!(
+                target = "token_key::signal",
+                cause = %err,
+            );
+            return;
+        }
+    };
+
+    while hangup.recv().await.is_some() {
+        match std::fs::read_to_string(&path) {
+            Ok(key) => match token_service.rotate_key(key.trim_end().as_bytes()) {
+                Ok(()) => tracing::info!(target = "token_key::reload", path = %path),
+                Err(err) => {
+                    tracing::error!(
+                        target = "token_key::reload",
+                        path = %path,
+                        cause = ?err,
+                    );
+                }
+            },
+            Err(err) => {
+                tracing::error!(
+                    target = "token_key::reload",
+                    path = %path,
+                    cause = %err,
+                );
+            }
+        }
+    }
+}
+
+/// Argon2 hash service, calibrated to the host when `ARGON2_AUTOTUNE` is set.
+fn create_hash_service() -> Argon2HashService {
+    let env = env_var::get();
+    if !env.argon2_autotune {
+        return Argon2HashService::new();
+    }
+
+    let target = Duration::from_millis(env.argon2_target_ms);
+    let params = Argon2HashService::autotune(target);
+    tracing::info!(
+        target = "startup::argon2_autotune",
+        target_ms = env.argon2_target_ms,
+        m_cost = params.m_cost(),
+        t_cost = params.t_cost(),
+        p_cost = params.p_cost(),
+    );
+
+    Argon2HashService::with_params(params)
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
 
+    tracing::info!(
+        target = "startup::config",
+        env = %env_var::get().redacted(),
+        database_min_connections = database::connection::MIN_CONNECTIONS,
+        database_max_connections = database::connection::MAX_CONNECTIONS,
+    );
+
     let pool = database::connection::create_sqlx_pool().await;
 
     let addr: SocketAddr = ([0, 0, 0, 0], env_var::get().port).into();
+    let token_service = Arc::new(JWTEncryptionService::from_config());
+    tracing::info!(
+        target = "startup::token_service",
+        algorithm = ?token_service.algorithm(),
+    );
+    #[cfg(unix)]
+    if let Some(path) = env_var::get().token_key_file.clone() {
+        tokio::spawn(handle_token_key_reload(token_service.clone(), path));
+    }
+
     let router = router::app(
         &pool,
-        Arc::new(Argon2HashService::new()),
-        Arc::new(JWTEncryptionService::from_config()),
+        Arc::new(CompositeHashService::new(create_hash_service())),
+        token_service,
+        Arc::new(DatabaseAuditSink::new(pool.clone())),
     );
     let listener = TcpListener::bind(&addr);
     Server::new(listener)
@@ -0,0 +1,54 @@
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serial_test::serial;
+
+use crate::setup::setup_test;
+
+mod setup;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlugPreviewResponse {
+    pub slug: String,
+}
+
+mod preview_slug {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn preview_slug() {
+        let (client, mut url, _) = setup_test().await;
+
+        url.set_path("/api/articles/slugify");
+        url.query_pairs_mut()
+            .append_pair("title", "How to train your dragon!");
+        let req = client.get(url).build().unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let preview: SlugPreviewResponse = res.json().await.unwrap();
+        assert_eq!(preview.slug, "how-to-train-your-dragon");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reject_blank_title() {
+        let (client, mut url, _) = setup_test().await;
+
+        url.set_path("/api/articles/slugify");
+        url.query_pairs_mut().append_pair("title", "   ");
+        let req = client.get(url.clone()).build().unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        url.set_query(None);
+        let req = client.get(url).build().unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,54 @@
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serial_test::serial;
+
+use crate::setup::setup_test;
+
+mod setup;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    pub missing_tables: Vec<String>,
+}
+
+mod readiness {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn ready_with_complete_schema() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client.get(url.join("/ready").unwrap()).build().unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let readiness: ReadinessResponse = res.json().await.unwrap();
+        assert!(readiness.ready);
+        assert_eq!(readiness.missing_tables, Vec::<String>::new());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn report_missing_table() {
+        let (client, url, pool) = setup_test().await;
+
+        sqlx::query("DROP TABLE iam.audit_log")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let req = client.get(url.join("/ready").unwrap()).build().unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let readiness: ReadinessResponse = res.json().await.unwrap();
+        assert!(!readiness.ready);
+        assert_eq!(readiness.missing_tables, vec!["iam.audit_log".to_string()]);
+    }
+}
@@ -0,0 +1,520 @@
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serial_test::serial;
+use url::Url;
+use uuid::Uuid;
+
+use crate::setup::{
+    setup_test,
+    user::{register_and_authenticate, register_and_authenticate_as, CreateUser, USER},
+};
+
+mod setup;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileResponse {
+    pub id: Uuid,
+    pub created: DateTime<Utc>,
+    pub updated: Option<DateTime<Utc>>,
+    pub version: u32,
+    pub username: String,
+    pub bio: Option<String>,
+    pub image_url: Option<Url>,
+    pub following: bool,
+}
+
+mod get_profile {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn find_profile_by_username() {
+        let (client, url, _) = setup_test().await;
+
+        let (id, _) = register_and_authenticate(&client, &url).await;
+
+        let req = client
+            .get(
+                url.join(&format!("/api/profiles/{}", USER.username))
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let profile: ProfileResponse = res.json().await.unwrap();
+        assert_eq!(profile.id, id);
+        assert_eq!(profile.username, USER.username);
+        assert_eq!(profile.bio, None);
+        assert!(!profile.following);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn not_following_profile_when_authenticated() {
+        let (client, url, _) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate(&client, &url).await;
+
+        let req = client
+            .get(
+                url.join(&format!("/api/profiles/{}", USER.username))
+                    .unwrap(),
+            )
+            .bearer_auth(&auth.token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let profile: ProfileResponse = res.json().await.unwrap();
+        assert_eq!(profile.username, USER.username);
+        assert!(!profile.following);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn resolve_me_alias_to_authenticated_user() {
+        let (client, url, _) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate(&client, &url).await;
+
+        let req = client
+            .get(url.join("/api/profiles/me").unwrap())
+            .bearer_auth(&auth.token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let profile: ProfileResponse = res.json().await.unwrap();
+        assert_eq!(profile.username, USER.username);
+        assert!(!profile.following);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn require_authentication_for_me_alias() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .get(url.join("/api/profiles/me").unwrap())
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reject_invalid_token() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .get(url.join("/api/profiles/user12345").unwrap())
+            .bearer_auth("invalid.jwt.token")
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn find_profile_by_username_case_insensitively() {
+        let (client, url, _) = setup_test().await;
+
+        let dto = CreateUser {
+            email: "user@email.com",
+            username: "User12345",
+            password: "12345678",
+        };
+        register_and_authenticate_as(&client, &url, &dto).await;
+
+        let req = client
+            .get(url.join("/api/profiles/user12345").unwrap())
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let profile: ProfileResponse = res.json().await.unwrap();
+        assert_eq!(profile.username, dto.username);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn not_found_unknown_username() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .get(url.join("/api/profiles/unknown_user").unwrap())
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
+
+mod follow_profile {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const FOLLOWER: CreateUser<'static> = CreateUser {
+        email: "follower@email.com",
+        username: "follower",
+        password: "12345678",
+    };
+
+    const FOLLOWING: CreateUser<'static> = CreateUser {
+        email: "following@email.com",
+        username: "following",
+        password: "12345678",
+    };
+
+    async fn send(req: reqwest::RequestBuilder) -> ProfileResponse {
+        let res = req.send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        res.json().await.unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn follow_profile() {
+        let (client, url, _) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate_as(&client, &url, &FOLLOWER).await;
+        register_and_authenticate_as(&client, &url, &FOLLOWING).await;
+
+        let follow_url = url.join("/api/profiles/following/follow").unwrap();
+        let profile = send(client.post(follow_url).bearer_auth(&auth.token)).await;
+        assert_eq!(profile.username, FOLLOWING.username);
+        assert!(profile.following);
+
+        let profile_url = url.join("/api/profiles/following").unwrap();
+        let profile = send(client.get(profile_url.clone()).bearer_auth(&auth.token)).await;
+        assert!(profile.following);
+
+        let profile = send(client.get(profile_url)).await;
+        assert!(!profile.following);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn follow_profile_idempotently() {
+        let (client, url, pool) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate_as(&client, &url, &FOLLOWER).await;
+        register_and_authenticate_as(&client, &url, &FOLLOWING).await;
+
+        let follow_url = url.join("/api/profiles/following/follow").unwrap();
+        for _ in 0..2 {
+            let profile = send(client.post(follow_url.clone()).bearer_auth(&auth.token)).await;
+            assert!(profile.following);
+        }
+
+        let follows: i64 = sqlx::query_scalar("SELECT count(*) FROM iam.follows")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(follows, 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn follow_profile_concurrently() {
+        let (client, url, pool) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate_as(&client, &url, &FOLLOWER).await;
+        register_and_authenticate_as(&client, &url, &FOLLOWING).await;
+
+        let follow_url = url.join("/api/profiles/following/follow").unwrap();
+        let follows = (0..2).map(|_| {
+            client
+                .post(follow_url.clone())
+                .bearer_auth(&auth.token)
+                .send()
+        });
+        let statuses: Vec<StatusCode> = futures::future::join_all(follows)
+            .await
+            .into_iter()
+            .map(|res| res.unwrap().status())
+            .collect();
+        assert_eq!(statuses, vec![StatusCode::OK; 2]);
+
+        let follows: i64 = sqlx::query_scalar("SELECT count(*) FROM iam.follows")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(follows, 1);
+    }
+
+    /// Follows of a user beyond the `FOLLOW_LIMIT` of the server are rejected,
+    /// unlimited when unset.
+    #[tokio::test]
+    #[serial]
+    async fn respect_follow_limit() {
+        let (client, url, _) = setup_test().await;
+        let limit = std::env::var("FOLLOW_LIMIT")
+            .map(|limit| limit.parse::<usize>().unwrap())
+            .ok();
+
+        let (_, auth) = register_and_authenticate_as(&client, &url, &FOLLOWER).await;
+
+        let profiles = limit.unwrap_or(2) + 1;
+        for n in 0..profiles {
+            let email = format!("following{n}@email.com");
+            let username = format!("following{n}");
+            register_and_authenticate_as(
+                &client,
+                &url,
+                &CreateUser {
+                    email: &email,
+                    username: &username,
+                    password: "12345678",
+                },
+            )
+            .await;
+
+            let follow_url = url
+                .join(&format!("/api/profiles/{username}/follow"))
+                .unwrap();
+            let res = client
+                .post(follow_url)
+                .bearer_auth(&auth.token)
+                .send()
+                .await
+                .unwrap();
+
+            if limit.map_or(true, |limit| n < limit) {
+                assert_eq!(res.status(), StatusCode::OK, "{username}");
+                continue;
+            }
+            assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{username}");
+            let err: serde_json::Value = res.json().await.unwrap();
+            assert_eq!(
+                err["error"],
+                serde_json::json!({ "Operation": "FollowLimitReached" })
+            );
+        }
+
+        // a already followed profile is still followed at the limit
+        if limit.map_or(false, |limit| limit > 0) {
+            let res = client
+                .post(url.join("/api/profiles/following0/follow").unwrap())
+                .bearer_auth(&auth.token)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn unfollow_profile() {
+        let (client, url, _) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate_as(&client, &url, &FOLLOWER).await;
+        register_and_authenticate_as(&client, &url, &FOLLOWING).await;
+
+        let follow_url = url.join("/api/profiles/following/follow").unwrap();
+        send(client.post(follow_url.clone()).bearer_auth(&auth.token)).await;
+
+        let res = client
+            .delete(follow_url.clone())
+            .bearer_auth(&auth.token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+        let profile_url = url.join("/api/profiles/following").unwrap();
+        let profile = send(client.get(profile_url).bearer_auth(&auth.token)).await;
+        assert!(!profile.following);
+
+        // unfollowing a not followed profile
+        let res = client
+            .delete(follow_url)
+            .bearer_auth(&auth.token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn audit_follow_and_unfollow() {
+        let (client, url, pool) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate_as(&client, &url, &FOLLOWER).await;
+        register_and_authenticate_as(&client, &url, &FOLLOWING).await;
+
+        let follow_url = url.join("/api/profiles/following/follow").unwrap();
+        for _ in 0..2 {
+            send(client.post(follow_url.clone()).bearer_auth(&auth.token)).await;
+        }
+        for _ in 0..2 {
+            let res = client
+                .delete(follow_url.clone())
+                .bearer_auth(&auth.token)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        }
+
+        // only the changes of the relation are recorded
+        let actions: Vec<String> = sqlx::query_scalar(concat!(
+            "SELECT action FROM iam.audit_log ",
+            "WHERE action IN ('followed', 'unfollowed') ORDER BY time"
+        ))
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(actions, vec!["followed", "unfollowed"]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reject_self_follow() {
+        let (client, url, _) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate_as(&client, &url, &FOLLOWER).await;
+
+        let res = client
+            .post(url.join("/api/profiles/follower/follow").unwrap())
+            .bearer_auth(&auth.token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let err: serde_json::Value = res.json().await.unwrap();
+        let field = &err["error"]["Validation"]["fields"][0];
+        assert_eq!(field["path"], "/username");
+        assert_eq!(field["kinds"], serde_json::json!(["Invalid"]));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn require_authentication() {
+        let (client, url, _) = setup_test().await;
+
+        register_and_authenticate_as(&client, &url, &FOLLOWING).await;
+
+        let res = client
+            .post(url.join("/api/profiles/following/follow").unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod following_status {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct FollowingStatusResponse {
+        following: HashMap<Uuid, bool>,
+    }
+
+    async fn profile_id(client: &reqwest::Client, url: &Url, username: &str) -> Uuid {
+        let res = client
+            .get(url.join(&format!("/api/profiles/{username}")).unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        res.json::<ProfileResponse>().await.unwrap().id
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reflect_the_follow_relation() {
+        let (client, url, _) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate_as(
+            &client,
+            &url,
+            &CreateUser {
+                email: "follower@email.com",
+                username: "follower",
+                password: "12345678",
+            },
+        )
+        .await;
+        for username in ["followed", "unfollowed"] {
+            let email = format!("{username}@email.com");
+            register_and_authenticate_as(
+                &client,
+                &url,
+                &CreateUser {
+                    email: &email,
+                    username,
+                    password: "12345678",
+                },
+            )
+            .await;
+        }
+
+        let res = client
+            .post(url.join("/api/profiles/followed/follow").unwrap())
+            .bearer_auth(&auth.token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let followed = profile_id(&client, &url, "followed").await;
+        let unfollowed = profile_id(&client, &url, "unfollowed").await;
+        let unknown = Uuid::new_v4();
+
+        let res = client
+            .post(url.join("/api/profile/following-status").unwrap())
+            .bearer_auth(&auth.token)
+            .json(&serde_json::json!({ "ids": [followed, unfollowed, unknown] }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let status: FollowingStatusResponse = res.json().await.unwrap();
+        assert_eq!(
+            status.following,
+            HashMap::from([(followed, true), (unfollowed, false), (unknown, false)])
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn require_authentication() {
+        let (client, url, _) = setup_test().await;
+
+        let res = client
+            .post(url.join("/api/profile/following-status").unwrap())
+            .json(&serde_json::json!({ "ids": [Uuid::new_v4()] }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+}
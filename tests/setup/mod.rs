@@ -6,6 +6,10 @@ use url::Url;
 
 use std::time::Duration;
 
+// not every test crate registers users
+#[allow(dead_code)]
+pub mod user;
+
 pub async fn setup_test() -> (Client, Url, sqlx::PgPool) {
     dotenv::dotenv().unwrap();
     (create_client(), service_url(), setup_database().await)
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserResource<T> {
+    pub user: T,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUser<'a> {
+    pub username: &'a str,
+    pub email: &'a str,
+    pub password: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserCredential<'a> {
+    pub email: &'a str,
+    pub password: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserResponse {
+    pub id: Uuid,
+    pub created: DateTime<Utc>,
+    pub updated: Option<DateTime<Utc>>,
+    pub version: u32,
+    pub email: String,
+    pub username: String,
+    pub bio: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthenticateUserResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    pub token: String,
+    pub refresh_token: String,
+}
+
+/// User registered by [`register_and_authenticate`].
+pub const USER: CreateUser<'static> = CreateUser {
+    email: "user@email.com",
+    username: "user12345",
+    password: "12345678",
+};
+
+/// Credential of the [`USER`].
+pub const CREDENTIAL: UserCredential<'static> = UserCredential {
+    email: USER.email,
+    password: USER.password,
+};
+
+/// Create a user, asserting it was accepted.
+pub async fn register(client: &Client, url: &Url, id: Uuid, dto: &CreateUser<'_>) -> UserResponse {
+    let req = client
+        .post(url.join(&format!("/api/user/{id}")).unwrap())
+        .json(&UserResource { user: dto })
+        .build()
+        .unwrap();
+
+    let res = client.execute(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    res.json::<UserResource<_>>().await.unwrap().user
+}
+
+/// Authenticate a user, asserting the credential was accepted.
+pub async fn authenticate(
+    client: &Client,
+    url: &Url,
+    credential: &UserCredential<'_>,
+) -> AuthenticateUserResponse {
+    let req = client
+        .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+        .json(&UserResource { user: credential })
+        .build()
+        .unwrap();
+
+    let res = client.execute(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    res.json::<UserResource<_>>().await.unwrap().user
+}
+
+/// Register the [`USER`] and authenticate it, returning the id of the user.
+pub async fn register_and_authenticate(
+    client: &Client,
+    url: &Url,
+) -> (Uuid, AuthenticateUserResponse) {
+    register_and_authenticate_as(client, url, &USER).await
+}
+
+/// Register a user and authenticate it, returning the id of the user.
+pub async fn register_and_authenticate_as(
+    client: &Client,
+    url: &Url,
+    dto: &CreateUser<'_>,
+) -> (Uuid, AuthenticateUserResponse) {
+    let id = Uuid::new_v4();
+    register(client, url, id, dto).await;
+
+    let credential = UserCredential {
+        email: dto.email,
+        password: dto.password,
+    };
+    (id, authenticate(client, url, &credential).await)
+}
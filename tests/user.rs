@@ -1,36 +1,132 @@
-use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serial_test::serial;
 use uuid::Uuid;
 
-use crate::setup::setup_test;
+use crate::setup::{
+    setup_test,
+    user::{
+        authenticate, register, register_and_authenticate, AuthenticateUserResponse, CreateUser,
+        UserCredential, UserResource, UserResponse, CREDENTIAL, USER,
+    },
+};
 
 mod setup;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CreateUser<'a> {
-    pub username: &'a str,
-    pub email: &'a str,
-    pub password: &'a str,
+pub struct UpdateUser<'a> {
+    pub bio: Option<&'a str>,
+    pub image_url: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserCredential<'a> {
-    pub email: &'a str,
-    pub password: &'a str,
+pub struct RefreshTokenBody<'a> {
+    pub refresh_token: &'a str,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserResponse {
-    pub id: Uuid,
-    pub created: DateTime<Utc>,
-    pub updated: Option<DateTime<Utc>>,
-    pub version: u32,
-    pub email: String,
-    pub username: String,
-    pub bio: Option<String>,
-    pub image_url: Option<String>,
+pub struct UserExportResponse {
+    pub user: UserResponse,
+    pub following: Vec<String>,
+    pub followers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorResponse<T> {
+    pub title: String,
+    pub message: String,
+    pub error: T,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictError {
+    pub resource_id: Option<Uuid>,
+    pub resource_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ConflictResponse {
+    Conflict(ConflictError),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationFieldError {
+    pub path: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationError {
+    pub fields: Vec<ValidationFieldError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ValidationResponse {
+    Validation(ValidationError),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub sub: String,
+    pub iss: String,
+    pub iat: u64,
+    pub exp: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenIntrospectionResponse {
+    #[serde(flatten)]
+    pub claims: TokenClaims,
+    pub aud: Option<String>,
+    pub jti: Option<String>,
+    pub expired: bool,
+    pub revoked: bool,
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Whether the server conceals the registered emails, by the `REGISTRATION_CONCEAL_EMAIL` flag.
+fn conceal_email() -> bool {
+    std::env::var("REGISTRATION_CONCEAL_EMAIL")
+        .map(|flag| flag.parse::<bool>().unwrap())
+        .unwrap_or(false)
+}
+
+fn token_issuer() -> String {
+    std::env::var("TOKEN_ISSUER").unwrap_or_else(|_| "conduit.blog.app".into())
+}
+
+/// Sign a token with the service key, as the service would issue it.
+fn sign_token(sub: &str, exp: u64) -> String {
+    #[derive(Serialize)]
+    struct Payload<'a> {
+        #[serde(flatten)]
+        claims: TokenClaims,
+        data: Option<&'a str>,
+    }
+
+    let key = std::env::var("TOKEN_KEY").unwrap();
+    let payload = Payload {
+        claims: TokenClaims {
+            sub: sub.into(),
+            iss: token_issuer(),
+            iat: now(),
+            exp,
+        },
+        data: None,
+    };
+
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256),
+        &payload,
+        &jsonwebtoken::EncodingKey::from_secret(key.as_bytes()),
+    )
+    .unwrap()
 }
 
 mod create_user {
@@ -38,6 +134,9 @@ mod create_user {
 
     use super::*;
 
+    /// Subset of RFC 5322 the server accepts as email.
+    const EMAIL_PATTERN: &str = r"^[a-z0-9!#$%&'*+/=?^_`{|}~-]+(\.[a-z0-9!#$%&'*+/=?^_`{|}~-]+)*@[a-z0-9]([a-z0-9-]*[a-z0-9])?(\.[a-z0-9]([a-z0-9-]*[a-z0-9])?)+$";
+
     #[tokio::test]
     #[serial]
     async fn create_user() {
@@ -50,8 +149,8 @@ mod create_user {
         };
 
         let req = client
-            .post(url.join("/api/user").unwrap())
-            .json(&dto)
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &dto })
             .build()
             .unwrap();
 
@@ -63,7 +162,7 @@ mod create_user {
             "invalid created user status code"
         );
 
-        let user: UserResponse = res.json().await.unwrap();
+        let user: UserResponse = res.json::<UserResource<_>>().await.unwrap().user;
 
         assert_eq!(user.email, dto.email);
         assert_eq!(user.username, dto.username);
@@ -82,118 +181,1734 @@ mod create_user {
             password: "secure:12345678",
         };
 
+        register(&client, &url, Uuid::new_v4(), &dto).await;
+
         let req = client
-            .post(url.join("/api/user").unwrap())
-            .json(&dto)
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &dto })
             .build()
             .unwrap();
 
         let res = client.execute(req).await.unwrap();
-        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn report_enveloped_field_path() {
+        let (client, url, _) = setup_test().await;
+
+        let dto = CreateUser {
+            email: "user@email.com",
+            username: "user12345",
+            password: "secure:12345678",
+        };
+
+        register(&client, &url, Uuid::new_v4(), &dto).await;
 
         let req = client
-            .post(url.join("/api/user").unwrap())
-            .json(&dto)
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource {
+                user: CreateUser {
+                    email: "other@email.com",
+                    ..dto
+                },
+            })
             .build()
             .unwrap();
 
         let res = client.execute(req).await.unwrap();
         assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let err: ErrorResponse<ValidationResponse> = res.json().await.unwrap();
+        let ValidationResponse::Validation(validation) = err.error;
+        let paths: Vec<&str> = validation
+            .fields
+            .iter()
+            .map(|field| field.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["/user/username"]);
     }
-}
 
-mod authenticate_user {
-    use pretty_assertions::assert_eq;
+    #[tokio::test]
+    #[serial]
+    async fn report_both_conflicting_fields() {
+        let (client, url, _) = setup_test().await;
 
-    use super::*;
+        let dto = CreateUser {
+            email: "user@email.com",
+            username: "user12345",
+            password: "secure:12345678",
+        };
+
+        register(&client, &url, Uuid::new_v4(), &dto).await;
+
+        let req = client
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &dto })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let err: ErrorResponse<ValidationResponse> = res.json().await.unwrap();
+        let ValidationResponse::Validation(validation) = err.error;
+        let fields: Vec<(&str, &str)> = validation
+            .fields
+            .iter()
+            .map(|field| (field.path.as_str(), field.value.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("/user/email", "user@email.com"),
+                ("/user/username", "user12345")
+            ]
+        );
+    }
 
     #[tokio::test]
     #[serial]
-    async fn authenticate_user() {
+    async fn describe_password_policy() {
         let (client, url, _) = setup_test().await;
 
         let dto = CreateUser {
             email: "user@email.com",
             username: "user12345",
-            password: "12345678",
+            password: "1234567",
         };
 
         let req = client
-            .post(url.join("/api/user").unwrap())
-            .json(&dto)
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &dto })
             .build()
             .unwrap();
 
         let res = client.execute(req).await.unwrap();
-        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
 
-        let credential = UserCredential {
+        let body = res.text().await.unwrap();
+        assert!(
+            !body.contains(dto.password),
+            "Expect the attempted password not to be responded"
+        );
+
+        let err: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let field = &err["error"]["Validation"]["fields"][0];
+        assert_eq!(field["path"], "/user/password");
+        assert_eq!(field["kinds"], serde_json::json!([{ "MinLength": 8 }]));
+        assert_eq!(
+            field["extension"],
+            serde_json::json!({
+                "password_policy": { "min_length": 8, "required_classes": [] }
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reject_password_equal_to_username() {
+        let (client, url, _) = setup_test().await;
+
+        let dto = CreateUser {
             email: "user@email.com",
-            password: "12345678",
+            username: "user12345",
+            password: "user12345",
         };
 
         let req = client
-            .post(url.join("/api/auth").unwrap())
-            .json(&credential)
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &dto })
             .build()
             .unwrap();
 
         let res = client.execute(req).await.unwrap();
-        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let err: serde_json::Value = res.json().await.unwrap();
+        let field = &err["error"]["Validation"]["fields"][0];
+        assert_eq!(field["path"], "/user/password");
+        assert_eq!(field["kinds"], serde_json::json!(["Invalid"]));
     }
 
     #[tokio::test]
     #[serial]
-    async fn validate_inexistent_user() {
+    async fn aggregate_field_errors() {
         let (client, url, _) = setup_test().await;
 
-        let credential = UserCredential {
+        let dto = CreateUser {
             email: "user@email.com",
-            password: "12345678",
+            username: "user12345",
+            password: "secure:12345678",
+        };
+
+        register(&client, &url, Uuid::new_v4(), &dto).await;
+
+        let cases = [
+            (
+                CreateUser {
+                    email: "not-an-email",
+                    username: "usr",
+                    password: "weak",
+                },
+                serde_json::json!({ "Pattern": EMAIL_PATTERN }),
+            ),
+            (
+                CreateUser {
+                    email: dto.email,
+                    username: "usr",
+                    password: "weak",
+                },
+                serde_json::json!("AlreadyExists"),
+            ),
+        ];
+
+        for (invalid, email_kind) in cases {
+            let req = client
+                .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+                .json(&UserResource { user: &invalid })
+                .build()
+                .unwrap();
+
+            let res = client.execute(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+            let err: serde_json::Value = res.json().await.unwrap();
+            let fields = err["error"]["Validation"]["fields"].as_array().unwrap();
+            let fields: Vec<(&str, &str, &serde_json::Value)> = fields
+                .iter()
+                .map(|field| {
+                    (
+                        field["path"].as_str().unwrap(),
+                        field["value"].as_str().unwrap(),
+                        &field["kinds"],
+                    )
+                })
+                .collect();
+
+            assert_eq!(
+                fields,
+                vec![
+                    (
+                        "/user/email",
+                        invalid.email,
+                        &serde_json::json!([email_kind])
+                    ),
+                    (
+                        "/user/username",
+                        invalid.username,
+                        &serde_json::json!([{ "MinLength": 4 }])
+                    ),
+                    (
+                        "/user/password",
+                        "",
+                        &serde_json::json!([{ "MinLength": 8 }])
+                    ),
+                ]
+            );
+            assert_eq!(err["error"]["Validation"]["resource"]["password"], "");
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn validate_username_charset() {
+        let (client, url, _) = setup_test().await;
+
+        let dto = CreateUser {
+            email: "user@email.com",
+            username: "user name",
+            password: "secure:12345678",
         };
 
         let req = client
-            .post(url.join("/api/auth").unwrap())
-            .json(&credential)
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &dto })
             .build()
             .unwrap();
 
         let res = client.execute(req).await.unwrap();
         assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let err: serde_json::Value = res.json().await.unwrap();
+        let field = &err["error"]["Validation"]["fields"][0];
+        assert_eq!(field["path"], "/user/username");
+        assert_eq!(
+            field["kinds"],
+            serde_json::json!([{ "Pattern": "^[A-Za-z0-9_-]+$" }])
+        );
     }
 
     #[tokio::test]
     #[serial]
-    async fn verify_user_password() {
+    async fn register_concurrent_signups_once() {
         let (client, url, _) = setup_test().await;
 
         let dto = CreateUser {
             email: "user@email.com",
             username: "user12345",
-            password: "12345678",
+            password: "secure:12345678",
+        };
+
+        let signups = (0..10).map(|_| {
+            let req = client
+                .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+                .json(&UserResource { user: &dto })
+                .build()
+                .unwrap();
+            client.execute(req)
+        });
+        let statuses: Vec<StatusCode> = futures::future::join_all(signups)
+            .await
+            .into_iter()
+            .map(|res| res.unwrap().status())
+            .collect();
+
+        let created = statuses
+            .iter()
+            .filter(|status| **status == StatusCode::CREATED)
+            .count();
+        assert_eq!(
+            created, 1,
+            "Expect a single signup to succeed, {statuses:?}"
+        );
+        assert!(
+            statuses.iter().all(|status| matches!(
+                *status,
+                StatusCode::CREATED | StatusCode::BAD_REQUEST | StatusCode::CONFLICT
+            )),
+            "Expect the other signups to be rejected, {statuses:?}"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn validate_username_case_insensitively() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource {
+                user: CreateUser {
+                    email: "user@email.com",
+                    username: "User12345",
+                    password: "secure:12345678",
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let req = client
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource {
+                user: CreateUser {
+                    email: "other@email.com",
+                    username: "user12345",
+                    password: "secure:12345678",
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Registration response of a already registered email, depends on the
+    /// `REGISTRATION_CONCEAL_EMAIL` flag of the server.
+    #[tokio::test]
+    #[serial]
+    async fn respond_duplicated_email_by_privacy_mode() {
+        let (client, url, _) = setup_test().await;
+        let conceal_email = conceal_email();
+
+        let req = client
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource {
+                user: CreateUser {
+                    email: "same@email.com",
+                    username: "user12345",
+                    password: "secure:12345678",
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        let created_status = res.status();
+
+        let req = client
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource {
+                user: CreateUser {
+                    email: "same@email.com",
+                    username: "other12345",
+                    password: "secure:12345678",
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+
+        if conceal_email {
+            assert_eq!(created_status, StatusCode::ACCEPTED);
+            assert_eq!(res.status(), StatusCode::ACCEPTED);
+        } else {
+            assert_eq!(created_status, StatusCode::CREATED);
+            assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn report_conflicting_id() {
+        let (client, url, _) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let req = client
+            .post(url.join(&format!("/api/user/{id}")).unwrap())
+            .json(&UserResource {
+                user: CreateUser {
+                    email: "some@email.com",
+                    username: "user12345",
+                    password: "secure:12345678",
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let req = client
+            .post(url.join(&format!("/api/user/{id}")).unwrap())
+            .json(&UserResource {
+                user: CreateUser {
+                    email: "other@email.com",
+                    username: "other12345",
+                    password: "secure:12345678",
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        let body: ErrorResponse<ConflictResponse> = res.json().await.unwrap();
+        let ConflictResponse::Conflict(conflict) = body.error;
+        assert_eq!(conflict.resource_id, Some(id));
+        assert_eq!(conflict.resource_type, "iam::User");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn prefer_return_minimal() {
+        let (client, url, _) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let dto = CreateUser {
+            email: "some@email.com",
+            username: "user12345",
+            password: "secure:12345678",
         };
 
         let req = client
-            .post(url.join("/api/user").unwrap())
-            .json(&dto)
+            .post(url.join(&format!("/api/user/{id}")).unwrap())
+            .header("prefer", "return=minimal")
+            .json(&UserResource { user: &dto })
             .build()
             .unwrap();
 
         let res = client.execute(req).await.unwrap();
         assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(
+            res.headers()
+                .get("location")
+                .map(|location| location.to_str().unwrap()),
+            Some(format!("/api/user/{id}").as_str())
+        );
+        assert_eq!(res.text().await.unwrap(), "");
+    }
 
-        let credential = UserCredential {
-            email: "user@email.com",
-            password: "wrong_pass",
+    #[tokio::test]
+    #[serial]
+    async fn prefer_return_representation() {
+        let (client, url, _) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let dto = CreateUser {
+            email: "some@email.com",
+            username: "user12345",
+            password: "secure:12345678",
         };
 
         let req = client
-            .post(url.join("/api/auth").unwrap())
-            .json(&credential)
+            .post(url.join(&format!("/api/user/{id}")).unwrap())
+            .header("prefer", "return=representation")
+            .json(&UserResource { user: &dto })
             .build()
             .unwrap();
 
         let res = client.execute(req).await.unwrap();
-        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.headers().get("location"), None);
+
+        let user: UserResponse = res.json::<UserResource<_>>().await.unwrap().user;
+        assert_eq!(user.id, id);
+        assert_eq!(user.email, dto.email);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reject_malformed_body() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .header("content-type", "application/json")
+            .body(r#"{"username": "user12345", "email": "#)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+mod authenticate_user {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn authenticate_user() {
+        let (client, url, _) = setup_test().await;
+
+        let dto = USER;
+
+        register(&client, &url, Uuid::new_v4(), &dto).await;
+
+        let credential = CREDENTIAL;
+
+        authenticate(&client, &url, &credential).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn normalize_email_case() {
+        let (client, url, _) = setup_test().await;
+
+        let dto = CreateUser {
+            email: "User@Email.com",
+            username: "user12345",
+            password: "12345678",
+        };
+
+        let user = register(&client, &url, Uuid::new_v4(), &dto).await;
+        assert_eq!(user.email, "user@email.com");
+
+        let credential = UserCredential {
+            email: "USER@email.COM",
+            password: "12345678",
+        };
+
+        authenticate(&client, &url, &credential).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn match_legacy_mixed_case_email() {
+        let (client, url, pool) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let dto = USER;
+        register(&client, &url, id, &dto).await;
+
+        sqlx::query("UPDATE iam.user SET email = 'User@Email.com' WHERE id = $1")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let credential = CREDENTIAL;
+        authenticate(&client, &url, &credential).await;
+
+        let dto = CreateUser {
+            email: USER.email,
+            username: "user54321",
+            password: "12345678",
+        };
+        let req = client
+            .post(url.join(&format!("/api/user/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &dto })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Authentication response of a unknown email, answered as a wrong password by the
+    /// `REGISTRATION_CONCEAL_EMAIL` flag of the server.
+    #[tokio::test]
+    #[serial]
+    async fn validate_inexistent_user() {
+        let (client, url, _) = setup_test().await;
+
+        let credential = UserCredential {
+            email: USER.email,
+            password: "attempted_secret_pass",
+        };
+
+        let req = client
+            .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &credential })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        if conceal_email() {
+            assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        } else {
+            assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        }
+
+        let body = res.text().await.unwrap();
+        assert!(!body.contains(credential.password), "{body}");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn not_respond_the_password_of_malformed_email() {
+        let (client, url, _) = setup_test().await;
+
+        let malformed = UserCredential {
+            email: "not an email",
+            password: "attempted_secret_pass",
+        };
+        let unknown = UserCredential {
+            email: "unknown@email.com",
+            ..malformed
+        };
+
+        let unknown_status = if conceal_email() {
+            StatusCode::UNAUTHORIZED
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        let attempts = [
+            (
+                format!("/api/auth/{}", Uuid::new_v4()),
+                &malformed,
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                "/api/user/reactivate".into(),
+                &malformed,
+                StatusCode::BAD_REQUEST,
+            ),
+            ("/api/user/reactivate".into(), &unknown, unknown_status),
+        ];
+        for (path, credential, status) in attempts {
+            let req = client
+                .post(url.join(&path).unwrap())
+                .json(&UserResource { user: credential })
+                .build()
+                .unwrap();
+
+            let res = client.execute(req).await.unwrap();
+            assert_eq!(res.status(), status, "{path}");
+
+            let body = res.text().await.unwrap();
+            assert!(!body.contains(credential.password), "{path}: {body}");
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn require_credential_fields() {
+        let (client, url, _) = setup_test().await;
+
+        for body in ["{}", r#"{"user":{}}"#] {
+            let req = client
+                .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+                .header("content-type", "application/json")
+                .body(body)
+                .build()
+                .unwrap();
+
+            let res = client.execute(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+            let err: ErrorResponse<ValidationResponse> = res.json().await.unwrap();
+            let ValidationResponse::Validation(validation) = err.error;
+            let paths: Vec<&str> = validation
+                .fields
+                .iter()
+                .map(|field| field.path.as_str())
+                .collect();
+            assert_eq!(paths, vec!["/user/email", "/user/password"]);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn verify_user_password() {
+        let (client, url, _) = setup_test().await;
+
+        let dto = USER;
+
+        register(&client, &url, Uuid::new_v4(), &dto).await;
+
+        let credential = UserCredential {
+            email: USER.email,
+            password: "wrong_pass",
+        };
+
+        let req = client
+            .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &credential })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn authenticate_with_basic_scheme() {
+        let (client, url, _) = setup_test().await;
+
+        let dto = USER;
+
+        register(&client, &url, Uuid::new_v4(), &dto).await;
+
+        let req = client
+            .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+            .basic_auth(dto.email, Some(dto.password))
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let auth: AuthenticateUserResponse = res.json::<UserResource<_>>().await.unwrap().user;
+        assert_eq!(auth.user.email, dto.email);
+
+        let req = client
+            .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+            .basic_auth(dto.email, Some("wrong_pass"))
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn audit_failed_login() {
+        use sqlx::Row;
+
+        let (client, url, pool) = setup_test().await;
+
+        let dto = USER;
+
+        register(&client, &url, Uuid::new_v4(), &dto).await;
+
+        let credential = UserCredential {
+            email: USER.email,
+            password: "attempted_secret_pass",
+        };
+
+        let req = client
+            .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &credential })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let rows = sqlx::query("SELECT action, actor_id, target, ip FROM iam.audit_log")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let row = &rows[0];
+        assert_eq!(row.get::<&str, _>("action"), "login_failed");
+        assert!(row.get::<Option<Uuid>, _>("actor_id").is_some());
+        assert_eq!(row.get::<Option<&str>, _>("target"), Some(credential.email));
+
+        for column in ["action", "target", "ip"] {
+            let value: Option<&str> = row.get(column);
+            assert!(!value.unwrap_or_default().contains(credential.password));
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn rehash_outdated_password_hash() {
+        use argon2::{password_hash::PasswordHasher, Algorithm, Argon2, Params, Version};
+        use password_hash::SaltString;
+
+        let (client, url, pool) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let dto = USER;
+
+        register(&client, &url, id, &dto).await;
+
+        // hash of the same password, with lower m, t and p params
+        let outdated = Argon2::new(
+            Algorithm::Argon2id,
+            Version::V0x13,
+            Params::new(16, 1, 1, Some(Params::DEFAULT_OUTPUT_LEN)).unwrap(),
+        );
+        let salt = SaltString::generate(&mut rand_core::OsRng);
+        let outdated_hash = outdated
+            .hash_password(dto.password.as_bytes(), &salt)
+            .unwrap()
+            .to_string();
+
+        sqlx::query("UPDATE iam.user SET password_hash = $1 WHERE id = $2")
+            .bind(&outdated_hash)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let credential = UserCredential {
+            email: dto.email,
+            password: dto.password,
+        };
+
+        for _ in 0..2 {
+            authenticate(&client, &url, &credential).await;
+
+            let password_hash: String =
+                sqlx::query_scalar("SELECT password_hash FROM iam.user WHERE id = $1")
+                    .bind(id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_ne!(password_hash, outdated_hash);
+        }
+    }
+}
+
+mod update_user {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn persist_updated_profile() {
+        let (client, url, _) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        let update = UpdateUser {
+            bio: Some("I write about databases"),
+            image_url: Some("https://images.conduit.blog/user12345.png"),
+        };
+
+        let req = client
+            .put(url.join(&format!("/api/user/{id}")).unwrap())
+            .bearer_auth(&auth.token)
+            .json(&UserResource { user: &update })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = client
+            .get(url.join("/api/user/export").unwrap())
+            .bearer_auth(&auth.token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let persisted = res.json::<UserExportResponse>().await.unwrap().user;
+        assert_eq!(persisted.bio.as_deref(), update.bio);
+        assert_eq!(persisted.image_url.as_deref(), update.image_url);
+        assert_eq!(persisted.version, auth.user.version + 1);
+        assert!(persisted.updated.is_some());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reject_stale_update() {
+        let (client, url, pool) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        // a concurrent update holds the row, after the request loaded the version it replaces
+        let mut trx = pool.begin().await.unwrap();
+        sqlx::query(
+            "UPDATE iam.user SET bio = 'concurrent bio', version = version + 1 WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&mut trx)
+        .await
+        .unwrap();
+
+        let req = client
+            .put(url.join(&format!("/api/user/{id}")).unwrap())
+            .bearer_auth(&auth.token)
+            .json(&UserResource {
+                user: UpdateUser {
+                    bio: Some("stale bio"),
+                    image_url: None,
+                },
+            })
+            .build()
+            .unwrap();
+        let stale = tokio::spawn(client.execute(req));
+
+        let mut waiting = false;
+        for _ in 0..100 {
+            let blocked: i64 =
+                sqlx::query_scalar("SELECT count(*) FROM pg_locks WHERE NOT granted")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            if blocked > 0 {
+                waiting = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(waiting, "Expect the stale update to wait on the row lock");
+        trx.commit().await.unwrap();
+
+        let res = stale.await.unwrap().unwrap();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        let (bio, version): (Option<String>, i64) =
+            sqlx::query_as("SELECT bio, version FROM iam.user WHERE id = $1")
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(bio.as_deref(), Some("concurrent bio"));
+        assert_eq!(version, 2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn authorize_before_loading_user() {
+        let (client, url, _) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let tokens = [
+            "some.authentication.token".to_string(),
+            sign_token(&format!("user:{}", Uuid::new_v4()), now() + 60 * 10),
+        ];
+
+        for token in tokens {
+            let req = client
+                .put(url.join(&format!("/api/user/{id}")).unwrap())
+                .bearer_auth(&token)
+                .json(&UserResource {
+                    user: UpdateUser {
+                        bio: Some("my bio"),
+                        image_url: None,
+                    },
+                })
+                .build()
+                .unwrap();
+
+            let res = client.execute(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
+
+mod refresh_token {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn refresh_access_token() {
+        let (client, url, _) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        // the refreshed token is issued at a later second
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+        let req = client
+            .post(url.join("/api/auth/refresh").unwrap())
+            .json(&RefreshTokenBody {
+                refresh_token: &auth.refresh_token,
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let refreshed: AuthenticateUserResponse = res.json::<UserResource<_>>().await.unwrap().user;
+        assert_eq!(refreshed.user.id, id);
+        assert_eq!(refreshed.refresh_token, auth.refresh_token);
+        assert_ne!(refreshed.token, auth.token);
+
+        let req = client
+            .get(url.join("/api/user/export").unwrap())
+            .bearer_auth(&refreshed.token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reject_token_of_other_kind() {
+        let (client, url, _) = setup_test().await;
+
+        let (_, auth) = register_and_authenticate(&client, &url).await;
+
+        let req = client
+            .post(url.join("/api/auth/refresh").unwrap())
+            .json(&RefreshTokenBody {
+                refresh_token: &auth.token,
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let req = client
+            .get(url.join("/api/user/export").unwrap())
+            .bearer_auth(&auth.refresh_token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod export_user {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn export_user_data() {
+        let (client, url, _) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        let req = client
+            .get(url.join("/api/user/export").unwrap())
+            .bearer_auth(&auth.token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let export: UserExportResponse = res.json().await.unwrap();
+        assert_eq!(export.user.id, id);
+        assert_eq!(export.user.email, USER.email);
+        assert_eq!(export.user.username, USER.username);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn export_follows() {
+        let (client, url, pool) = setup_test().await;
+
+        let users = [
+            ("user@email.com", "user12345"),
+            ("followed@email.com", "followed"),
+            ("follower@email.com", "follower"),
+            ("inactive@email.com", "inactive"),
+        ];
+        let mut tokens = Vec::new();
+        for (email, username) in users {
+            let dto = CreateUser {
+                email,
+                username,
+                password: "12345678",
+            };
+            register(&client, &url, Uuid::new_v4(), &dto).await;
+
+            let credential = UserCredential {
+                email,
+                password: dto.password,
+            };
+            tokens.push(authenticate(&client, &url, &credential).await.token);
+        }
+
+        let follows = [
+            (&tokens[0], "followed"),
+            (&tokens[0], "inactive"),
+            (&tokens[2], "user12345"),
+            (&tokens[3], "user12345"),
+        ];
+        for (token, username) in follows {
+            let req = client
+                .post(
+                    url.join(&format!("/api/profiles/{username}/follow"))
+                        .unwrap(),
+                )
+                .bearer_auth(token)
+                .build()
+                .unwrap();
+
+            let res = client.execute(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+
+        // deactivated accounts are hidden from the follows
+        sqlx::query("UPDATE iam.user SET deactivated = true WHERE username = 'inactive'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let req = client
+            .get(url.join("/api/user/export").unwrap())
+            .bearer_auth(&tokens[0])
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let export: UserExportResponse = res.json().await.unwrap();
+        assert_eq!(export.following, vec!["followed".to_string()]);
+        assert_eq!(export.followers, vec!["follower".to_string()]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn require_authentication() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .get(url.join("/api/user/export").unwrap())
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod deactivate_user {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn deactivate_and_reactivate_user() {
+        let (client, url, _) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        let req = client
+            .post(url.join(&format!("/api/user/{id}/deactivate")).unwrap())
+            .bearer_auth(&auth.token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = client
+            .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource { user: &CREDENTIAL })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let req = client
+            .post(url.join("/api/user/reactivate").unwrap())
+            .json(&UserResource { user: &CREDENTIAL })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        authenticate(&client, &url, &CREDENTIAL).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn audit_deactivation() {
+        use sqlx::Row;
+
+        let (client, url, pool) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        let res = client
+            .post(url.join("/api/user/me/deactivate").unwrap())
+            .bearer_auth(&auth.token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let rows = sqlx::query(
+            "SELECT actor_id, target FROM iam.audit_log WHERE action = 'account_deactivated'",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<Option<Uuid>, _>("actor_id"), Some(id));
+        let target = id.to_string();
+        assert_eq!(
+            rows[0].get::<Option<&str>, _>("target"),
+            Some(target.as_str())
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reject_token_issued_before_deactivation() {
+        let (client, url, _) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        let followed = CreateUser {
+            email: "followed@email.com",
+            username: "followed",
+            password: "12345678",
+        };
+        register(&client, &url, Uuid::new_v4(), &followed).await;
+
+        let req = client
+            .post(url.join(&format!("/api/user/{id}/deactivate")).unwrap())
+            .bearer_auth(&auth.token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let follow_url = url.join("/api/profiles/followed/follow").unwrap();
+        let requests = [
+            client
+                .put(url.join(&format!("/api/user/{id}")).unwrap())
+                .json(&UserResource {
+                    user: UpdateUser {
+                        bio: Some("some bio"),
+                        image_url: None,
+                    },
+                }),
+            client.get(url.join("/api/user/export").unwrap()),
+            client.post(url.join(&format!("/api/user/{id}/deactivate")).unwrap()),
+            client.post(follow_url.clone()),
+            client.delete(follow_url),
+        ];
+        for req in requests {
+            let req = req.bearer_auth(&auth.token).build().unwrap();
+            let path = req.url().path().to_string();
+
+            let res = client.execute(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{path}");
+        }
+    }
+}
+
+mod not_found {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn inexistent_path_resource() {
+        let (client, url, _) = setup_test().await;
+
+        // a valid token of a user no longer registered
+        let id = Uuid::new_v4();
+        let req = client
+            .put(url.join(&format!("/api/user/{id}")).unwrap())
+            .bearer_auth(sign_token(&format!("user:{id}"), now() + 60 * 10))
+            .json(&UserResource {
+                user: UpdateUser {
+                    bio: Some("some bio"),
+                    image_url: None,
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn inexistent_body_reference() {
+        let (client, url, _) = setup_test().await;
+
+        let credential = CREDENTIAL;
+
+        let req = client
+            .post(url.join("/api/user/reactivate").unwrap())
+            .json(&UserResource { user: &credential })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+mod entity_tag {
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    use super::*;
+
+    fn etag(res: &reqwest::Response) -> String {
+        res.headers()
+            .get("etag")
+            .expect("Expect a etag header")
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn change_etag_on_update() {
+        let (client, url, _) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let dto = USER;
+
+        let req = client
+            .post(url.join(&format!("/api/user/{id}")).unwrap())
+            .json(&UserResource { user: &dto })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let created_etag = etag(&res);
+        assert_eq!(created_etag, "\"1\"");
+
+        let credential = UserCredential {
+            email: dto.email,
+            password: dto.password,
+        };
+
+        let auth = authenticate(&client, &url, &credential).await;
+
+        let req = client
+            .put(url.join(&format!("/api/user/{id}")).unwrap())
+            .bearer_auth(&auth.token)
+            .json(&UserResource {
+                user: UpdateUser {
+                    bio: Some("my bio"),
+                    image_url: None,
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let updated_etag = etag(&res);
+        assert_ne!(updated_etag, created_etag);
+
+        let user: UserResponse = res.json::<UserResource<_>>().await.unwrap().user;
+        assert_eq!(user.version, 2);
+        assert_eq!(updated_etag, "\"2\"");
+    }
+}
+
+mod updated_timestamp {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn set_updated_on_update() {
+        let (client, url, _) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let dto = USER;
+
+        let req = client
+            .post(url.join(&format!("/api/user/{id}")).unwrap())
+            .json(&UserResource { user: &dto })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(
+            body["user"].get("updated"),
+            Some(&serde_json::Value::Null),
+            "Expect a never updated user to respond updated as null"
+        );
+        let created: UserResponse = serde_json::from_value(body["user"].clone()).unwrap();
+        assert_eq!(created.updated, None);
+
+        let credential = UserCredential {
+            email: dto.email,
+            password: dto.password,
+        };
+
+        let auth = authenticate(&client, &url, &credential).await;
+
+        let req = client
+            .put(url.join(&format!("/api/user/{id}")).unwrap())
+            .bearer_auth(&auth.token)
+            .json(&UserResource {
+                user: UpdateUser {
+                    bio: Some("my bio"),
+                    image_url: None,
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let updated: UserResponse = res.json::<UserResource<_>>().await.unwrap().user;
+
+        let updated_time = updated.updated.expect("Expect the update time to be set");
+        assert!(updated_time > created.created);
+    }
+}
+
+mod me_alias {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn resolve_authenticated_user() {
+        let (client, url, _) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        let req = client
+            .put(url.join("/api/user/me").unwrap())
+            .bearer_auth(&auth.token)
+            .json(&UserResource {
+                user: UpdateUser {
+                    bio: Some("my bio"),
+                    image_url: None,
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let user: UserResponse = res.json::<UserResource<_>>().await.unwrap().user;
+        assert_eq!(user.id, id);
+        assert_eq!(user.bio.as_deref(), Some("my bio"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn require_authentication() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .put(url.join("/api/user/me").unwrap())
+            .json(&UserResource {
+                user: UpdateUser {
+                    bio: Some("my bio"),
+                    image_url: None,
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod public_token {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn deny_update_user() {
+        let (client, url, _) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let dto = USER;
+
+        register(&client, &url, id, &dto).await;
+
+        let token = sign_token("public", now() + 60 * 10);
+        for path in [format!("/api/user/{id}"), "/api/user/me".into()] {
+            let req = client
+                .put(url.join(&path).unwrap())
+                .bearer_auth(&token)
+                .json(&UserResource {
+                    user: UpdateUser {
+                        bio: Some("my bio"),
+                        image_url: None,
+                    },
+                })
+                .build()
+                .unwrap();
+
+            let res = client.execute(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn deny_export_user() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .get(url.join("/api/user/export").unwrap())
+            .bearer_auth(sign_token("public", now() + 60 * 10))
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+}
+
+mod introspect_token {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn reflect_token_claims() {
+        let (client, url, _) = setup_test().await;
+
+        let (id, auth) = register_and_authenticate(&client, &url).await;
+
+        let req = client
+            .get(url.join("/api/auth/introspect").unwrap())
+            .bearer_auth(&auth.token)
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let introspection: TokenIntrospectionResponse = res.json().await.unwrap();
+        assert_eq!(introspection.claims.sub, format!("user:{id}"));
+        assert_eq!(introspection.claims.iss, token_issuer());
+        assert!(introspection.claims.iat <= introspection.claims.exp);
+        assert_eq!(introspection.aud, None);
+        assert_eq!(introspection.jti, None);
+        assert!(!introspection.expired);
+        assert!(!introspection.revoked);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn flag_expired_token() {
+        let (client, url, _) = setup_test().await;
+
+        // within and beyond the verification leeway
+        for exp in [now() - 10, now() - 60 * 60] {
+            let req = client
+                .get(url.join("/api/auth/introspect").unwrap())
+                .bearer_auth(sign_token("public", exp))
+                .build()
+                .unwrap();
+
+            let res = client.execute(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+
+            let introspection: TokenIntrospectionResponse = res.json().await.unwrap();
+            assert_eq!(introspection.claims.sub, "public");
+            assert_eq!(introspection.claims.exp, exp);
+            assert!(introspection.expired);
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn require_authentication() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .get(url.join("/api/auth/introspect").unwrap())
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+mod jwks {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn not_publish_the_hs256_secret() {
+        let (client, url, _) = setup_test().await;
+
+        let req = client
+            .get(url.join("/.well-known/jwks.json").unwrap())
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
+
+mod password_hash_exposure {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn assert_no_password_hash(body: &str, password_hash: &str) {
+        assert!(
+            !body.contains(password_hash),
+            "Expect the password hash not to be in the response: {body}"
+        );
+        assert!(
+            !body.contains("password_hash"),
+            "Expect the password_hash key not to be in the response: {body}"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn never_respond_password_hash() {
+        let (client, url, pool) = setup_test().await;
+
+        let id = Uuid::new_v4();
+        let dto = USER;
+
+        let req = client
+            .post(url.join(&format!("/api/user/{id}")).unwrap())
+            .json(&UserResource { user: &dto })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        let mut bodies = vec![res.text().await.unwrap()];
+
+        let password_hash: String =
+            sqlx::query_scalar("SELECT password_hash FROM iam.user WHERE id = $1")
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        let req = client
+            .post(url.join(&format!("/api/auth/{}", Uuid::new_v4())).unwrap())
+            .json(&UserResource {
+                user: UserCredential {
+                    email: dto.email,
+                    password: dto.password,
+                },
+            })
+            .build()
+            .unwrap();
+
+        let res = client.execute(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = res.text().await.unwrap();
+        let auth: UserResource<AuthenticateUserResponse> = serde_json::from_str(&body).unwrap();
+        let token = auth.user.token;
+        bodies.push(body);
+
+        let requests = [
+            client
+                .get(
+                    url.join(&format!("/api/profiles/{}", dto.username))
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+            client
+                .put(url.join("/api/user/me").unwrap())
+                .bearer_auth(&token)
+                .json(&UserResource {
+                    user: UpdateUser {
+                        bio: Some("my bio"),
+                        image_url: None,
+                    },
+                })
+                .build()
+                .unwrap(),
+            client
+                .get(url.join("/api/user/export").unwrap())
+                .bearer_auth(&token)
+                .build()
+                .unwrap(),
+            client
+                .get(url.join("/api/auth/introspect").unwrap())
+                .bearer_auth(&token)
+                .build()
+                .unwrap(),
+            client
+                .post(url.join("/api/user/me/deactivate").unwrap())
+                .bearer_auth(&token)
+                .build()
+                .unwrap(),
+            client
+                .post(url.join("/api/user/reactivate").unwrap())
+                .json(&UserResource {
+                    user: UserCredential {
+                        email: dto.email,
+                        password: dto.password,
+                    },
+                })
+                .build()
+                .unwrap(),
+        ];
+
+        for req in requests {
+            let res = client.execute(req).await.unwrap();
+            assert!(res.status().is_success());
+            bodies.push(res.text().await.unwrap());
+        }
+
+        for body in &bodies {
+            assert_no_password_hash(body, &password_hash);
+        }
     }
 }